@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+};
+
+// Pool creation interface `migrate` CPIs into. Any AMM exposing an Anchor-style
+// `initialize_pool(amount_a: u64, amount_b: u64)` with this account order can be used;
+// `programs/mock-amm` implements it for local testing.
+pub const INITIALIZE_POOL_DISCRIMINATOR: [u8; 8] = [95, 180, 10, 172, 84, 174, 232, 40];
+
+pub struct InitializePool<'info> {
+    pub payer: AccountInfo<'info>,
+    pub creator: AccountInfo<'info>,
+    pub mint_a: AccountInfo<'info>,
+    pub mint_b: AccountInfo<'info>,
+    pub pool: AccountInfo<'info>,
+    pub lp_mint: AccountInfo<'info>,
+    pub vault_a: AccountInfo<'info>,
+    pub vault_b: AccountInfo<'info>,
+    pub creator_token_a: AccountInfo<'info>,
+    pub creator_token_b: AccountInfo<'info>,
+    pub creator_lp: AccountInfo<'info>,
+    pub token_program_a: AccountInfo<'info>,
+    pub token_program_b: AccountInfo<'info>,
+    pub lp_token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> InitializePool<'info> {
+    fn to_account_infos(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.payer.clone(),
+            self.creator.clone(),
+            self.mint_a.clone(),
+            self.mint_b.clone(),
+            self.pool.clone(),
+            self.lp_mint.clone(),
+            self.vault_a.clone(),
+            self.vault_b.clone(),
+            self.creator_token_a.clone(),
+            self.creator_token_b.clone(),
+            self.creator_lp.clone(),
+            self.token_program_a.clone(),
+            self.token_program_b.clone(),
+            self.lp_token_program.clone(),
+            self.associated_token_program.clone(),
+            self.system_program.clone(),
+        ]
+    }
+
+    fn to_account_metas(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.payer.key(), true),
+            AccountMeta::new_readonly(self.creator.key(), true),
+            AccountMeta::new_readonly(self.mint_a.key(), false),
+            AccountMeta::new_readonly(self.mint_b.key(), false),
+            AccountMeta::new(self.pool.key(), false),
+            AccountMeta::new(self.lp_mint.key(), false),
+            AccountMeta::new(self.vault_a.key(), false),
+            AccountMeta::new(self.vault_b.key(), false),
+            AccountMeta::new(self.creator_token_a.key(), false),
+            AccountMeta::new(self.creator_token_b.key(), false),
+            AccountMeta::new(self.creator_lp.key(), false),
+            AccountMeta::new_readonly(self.token_program_a.key(), false),
+            AccountMeta::new_readonly(self.token_program_b.key(), false),
+            AccountMeta::new_readonly(self.lp_token_program.key(), false),
+            AccountMeta::new_readonly(self.associated_token_program.key(), false),
+            AccountMeta::new_readonly(self.system_program.key(), false),
+        ]
+    }
+}
+
+pub fn initialize_pool<'info>(
+    amm_program: &AccountInfo<'info>,
+    accounts: InitializePool<'info>,
+    amount_a: u64,
+    amount_b: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut data = INITIALIZE_POOL_DISCRIMINATOR.to_vec();
+    data.extend_from_slice(&amount_a.to_le_bytes());
+    data.extend_from_slice(&amount_b.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: amm_program.key(),
+        accounts: accounts.to_account_metas(),
+        data,
+    };
+    let mut infos = accounts.to_account_infos();
+    infos.push(amm_program.clone());
+    invoke_signed(&ix, &infos, signer_seeds)?;
+
+    Ok(())
+}
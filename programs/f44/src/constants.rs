@@ -4,6 +4,46 @@ use anchor_lang::prelude::*;
 pub const GLOBAL_STATE_SEED: &[u8] = b"GLOBAL-STATE-SEED";
 pub const F44_VAULT_SEED: &[u8] = b"F44-VAULT-SEED";
 pub const MINT_SEED: &[u8] = b"mint";
+pub const METADATA_SEED: &[u8] = b"metadata";
 pub const BONDING_CURVE: &[u8] = b"BONDING-CURVE";
 pub const VAULT_SEED: &[u8] = b"VAULT-SEED";
-pub const SOL_VAULT_SEED: &[u8] = b"SOL-VAULT-SEED";
\ No newline at end of file
+pub const SOL_VAULT_SEED: &[u8] = b"SOL-VAULT-SEED";
+pub const POOL_SEED: &[u8] = b"POOL-SEED";
+pub const POSITION_SEED: &[u8] = b"POSITION-SEED";
+pub const REFERRAL_SEED: &[u8] = b"REFERRAL-SEED";
+pub const PRICE_FEED_SEED: &[u8] = b"PRICE-FEED-SEED";
+pub const QUOTE_CONFIG_SEED: &[u8] = b"QUOTE-CONFIG-SEED";
+pub const ADMIN_SET_SEED: &[u8] = b"ADMIN-SET-SEED";
+pub const ADMIN_ACTION_SEED: &[u8] = b"ADMIN-ACTION-SEED";
+
+pub const DEFAULT_MCAP_LIMIT: u64 = 100_000; // in whole F44 tokens
+// lowest lamport threshold a SOL curve may graduate on, the F44 bounds don't apply to lamports
+pub const MIN_SOL_GRADUATION_THRESHOLD: u64 = 1_000_000_000;
+
+pub const AGENT_TOKEN_DECIMALS: u8 = 6;
+pub const AGENT_TOKEN_SUPPLY: u64 = 1_000_000_000; // in whole agent tokens
+
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_TRADE_FEE_BPS: u64 = 1_000;
+pub const MAX_CREATOR_FEE_BPS: u64 = 500;
+// share of a curve's supply its buys never take, left to seed the built-in pool (or paid out
+// by `withdraw`) when a buy sells the curve out
+pub const POOL_RESERVE_BPS: u64 = 100;
+// swap fee of the built-in pool, left in the reserves for liquidity providers
+pub const POOL_FEE_BPS: u64 = 25;
+
+// members of the admin multisig, approvals are tracked as one bit per member
+pub const MAX_ADMIN_MEMBERS: usize = 10;
+
+// longest `set_params` timelock, so a typo cannot freeze the params for good
+pub const MAX_PARAMS_DELAY: u64 = 30 * 24 * 60 * 60;
+
+// highest create fee in whole F44 tokens, keeps `fee_amount * 10^decimals` in range
+pub const MAX_FEE_AMOUNT: u64 = 1_000_000_000;
+
+// Token Metadata program limits
+pub const MAX_NAME_LENGTH: usize = 32;
+pub const MAX_SYMBOL_LENGTH: usize = 10;
+pub const MAX_URI_LENGTH: usize = 200;
+
+pub const MAX_PAUSE_REASON_LENGTH: usize = 200;
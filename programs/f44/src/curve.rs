@@ -0,0 +1,531 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{
+    error::F44Code,
+    math::{div_round, exp, market_cap, mul_div, sqrt, to_u64, Rounding, MAX_EXP_ARG, PRICE_SCALE},
+    state::{BondingCurve, CurveKind, GraduationMetric},
+};
+
+// Shared pricing for buy and sell. `reserves` is the amount of agent tokens already
+// sold by the curve, in base units. Costs round up and proceeds round down, so a buy
+// followed by a sell of the same amount can never pay out more than it took in.
+impl CurveKind {
+    // Rejects parameters that would make the curve free or impossible to evaluate
+    // anywhere between zero and `total_supply` tokens sold.
+    pub fn validate(&self, total_supply: u64) -> Result<()> {
+        match *self {
+            CurveKind::Linear { initial_price, .. } => {
+                require!(initial_price > 0, F44Code::InvalidPrice);
+            }
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+            } => {
+                require!(initial_price > 0, F44Code::InvalidPrice);
+                require!(growth_rate > 0, F44Code::InvalidSlope);
+                let max_exponent = growth_rate
+                    .checked_mul(total_supply as u128)
+                    .ok_or(F44Code::InvalidSlope)?;
+                require!(max_exponent <= MAX_EXP_ARG, F44Code::InvalidSlope);
+            }
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves,
+                virtual_token_reserves,
+            } => {
+                require!(virtual_f44_reserves > 0, F44Code::InvalidReserves);
+                require!(
+                    virtual_token_reserves > total_supply,
+                    F44Code::InvalidReserves
+                );
+            }
+        }
+        // the top of the curve must be representable
+        self.price_at(total_supply)?;
+        Ok(())
+    }
+
+    pub fn price_at(&self, reserves: u64) -> Result<u128> {
+        match *self {
+            CurveKind::Linear {
+                initial_price,
+                curve_slope,
+            } => curve_slope
+                .checked_mul(reserves as u128)
+                .and_then(|v| v.checked_add(initial_price))
+                .ok_or(error!(F44Code::MathOverflow)),
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+            } => {
+                let growth = exp(exponent(growth_rate, reserves)?)?;
+                mul_div(initial_price, growth, PRICE_SCALE, Rounding::Down)
+            }
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves,
+                virtual_token_reserves,
+            } => {
+                let token_side = remaining_tokens(virtual_token_reserves, reserves)?;
+                let f44_side = div_round(
+                    invariant(virtual_f44_reserves, virtual_token_reserves),
+                    token_side,
+                    Rounding::Down,
+                )?;
+                mul_div(f44_side, PRICE_SCALE, token_side, Rounding::Down)
+            }
+        }
+    }
+
+    pub fn cost_to_buy(&self, reserves: u64, amount: u64) -> Result<u64> {
+        require!(amount > 0, F44Code::ZeroAmount);
+        let to = reserves
+            .checked_add(amount)
+            .ok_or(F44Code::InvalidReserves)?;
+
+        self.area(reserves, to, Rounding::Up)
+    }
+
+    // Largest amount, up to `max_amount`, whose `cost_to_buy` fits in `budget`.
+    pub fn tokens_for_cost(&self, reserves: u64, budget: u64, max_amount: u64) -> Result<u64> {
+        let estimate = match *self {
+            CurveKind::Linear {
+                initial_price,
+                curve_slope,
+            } => linear_tokens_for_cost(initial_price, curve_slope, reserves, budget),
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves,
+                virtual_token_reserves,
+            } => {
+                // ceil(k / (T - to)) <= floor(k / (T - from)) + budget, solved for `to`
+                let k = invariant(virtual_f44_reserves, virtual_token_reserves);
+                let remaining = remaining_tokens(virtual_token_reserves, reserves)?;
+                let low = div_round(k, remaining, Rounding::Down)?;
+                let high = low.checked_add(budget as u128).ok_or(F44Code::MathOverflow)?;
+                Some(remaining.saturating_sub(div_round(k, high, Rounding::Up)?))
+            }
+            CurveKind::Exponential { .. } => None,
+        };
+
+        match estimate {
+            Some(amount) => {
+                let mut amount = to_u64(amount.min(max_amount as u128))?;
+                // the closed forms are exact, this only guards against an off-by-one
+                while amount > 0 && self.cost_to_buy(reserves, amount)? > budget {
+                    amount -= 1;
+                }
+                Ok(amount)
+            }
+            None => self.search_tokens_for_cost(reserves, budget, max_amount),
+        }
+    }
+
+    // Bisection on `cost_to_buy`, for curves without a closed-form inverse.
+    fn search_tokens_for_cost(&self, reserves: u64, budget: u64, max_amount: u64) -> Result<u64> {
+        let (mut low, mut high) = (0u64, max_amount);
+        while low < high {
+            let mid = high - (high - low) / 2;
+            if self.cost_to_buy(reserves, mid)? <= budget {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    // Smallest amount whose `proceeds_from_sell` reaches `target`.
+    pub fn tokens_for_proceeds(&self, reserves: u64, target: u64) -> Result<u64> {
+        require!(target > 0, F44Code::ZeroAmount);
+        require!(
+            self.area(0, reserves, Rounding::Down)? >= target,
+            F44Code::InsufficientLiquidity
+        );
+
+        let estimate = match *self {
+            CurveKind::Linear {
+                initial_price,
+                curve_slope,
+            } => linear_tokens_for_proceeds(initial_price, curve_slope, reserves, target),
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves,
+                virtual_token_reserves,
+            } => {
+                // ceil(k / (T - from)) <= floor(k / (T - to)) - target, solved for `from`
+                let k = invariant(virtual_f44_reserves, virtual_token_reserves);
+                let remaining = remaining_tokens(virtual_token_reserves, reserves)?;
+                let high = div_round(k, remaining, Rounding::Down)?;
+                let low = high
+                    .checked_sub(target as u128)
+                    .filter(|low| *low > 0)
+                    .ok_or(F44Code::InsufficientLiquidity)?;
+                Some(div_round(k, low, Rounding::Up)?.saturating_sub(remaining))
+            }
+            CurveKind::Exponential { .. } => None,
+        };
+
+        match estimate {
+            Some(amount) => {
+                let mut amount = to_u64(amount.clamp(1, reserves as u128))?;
+                // the closed forms can land a unit off after rounding
+                while amount < reserves && self.proceeds_from_sell(reserves, amount)? < target {
+                    amount += 1;
+                }
+                while amount > 1 && self.proceeds_from_sell(reserves, amount - 1)? >= target {
+                    amount -= 1;
+                }
+                Ok(amount)
+            }
+            None => self.search_tokens_for_proceeds(reserves, target),
+        }
+    }
+
+    // Bisection on `proceeds_from_sell`, for curves without a closed-form inverse.
+    fn search_tokens_for_proceeds(&self, reserves: u64, target: u64) -> Result<u64> {
+        let (mut low, mut high) = (1u64, reserves);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.proceeds_from_sell(reserves, mid)? >= target {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        Ok(low)
+    }
+
+    pub fn proceeds_from_sell(&self, reserves: u64, amount: u64) -> Result<u64> {
+        require!(amount > 0, F44Code::ZeroAmount);
+        let from = reserves
+            .checked_sub(amount)
+            .ok_or(F44Code::InvalidReserves)?;
+
+        self.area(from, reserves, Rounding::Down)
+    }
+
+    // F44 base units paid into the curve while moving from `from` to `to` tokens sold.
+    fn area(&self, from: u64, to: u64, rounding: Rounding) -> Result<u64> {
+        match *self {
+            CurveKind::Linear {
+                initial_price,
+                curve_slope,
+            } => {
+                // the trapezoid is kept doubled until the final division so the midpoint is never rounded
+                let width = (to - from) as u128;
+                let base = initial_price
+                    .checked_mul(width)
+                    .and_then(|v| v.checked_mul(2))
+                    .ok_or(F44Code::MathOverflow)?;
+                let rise = curve_slope
+                    .checked_mul(width)
+                    .and_then(|v| v.checked_mul(from as u128 + to as u128))
+                    .ok_or(F44Code::MathOverflow)?;
+                let doubled = base.checked_add(rise).ok_or(F44Code::MathOverflow)?;
+
+                to_u64(div_round(doubled, 2 * PRICE_SCALE, rounding)?)
+            }
+            CurveKind::Exponential {
+                initial_price,
+                growth_rate,
+            } => {
+                // initial_price * (e^(k * to) - e^(k * from)) / k
+                let high = exp(exponent(growth_rate, to)?)?;
+                let low = exp(exponent(growth_rate, from)?)?;
+                let growth = high.checked_sub(low).ok_or(F44Code::InvalidCalculation)?;
+                let width = div_round(growth, growth_rate, rounding)?;
+
+                to_u64(mul_div(initial_price, width, PRICE_SCALE, rounding)?)
+            }
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves,
+                virtual_token_reserves,
+            } => {
+                // k / (T - to) - k / (T - from), each side rounded against the user
+                let k = invariant(virtual_f44_reserves, virtual_token_reserves);
+                let (high_rounding, low_rounding) = match rounding {
+                    Rounding::Up => (Rounding::Up, Rounding::Down),
+                    Rounding::Down => (Rounding::Down, Rounding::Up),
+                };
+                let high = div_round(
+                    k,
+                    remaining_tokens(virtual_token_reserves, to)?,
+                    high_rounding,
+                )?;
+                let low = div_round(
+                    k,
+                    remaining_tokens(virtual_token_reserves, from)?,
+                    low_rounding,
+                )?;
+
+                to_u64(high.saturating_sub(low))
+            }
+        }
+    }
+}
+
+// Positive root of slope * a^2 + 2 * (initial_price + slope * reserves) * a = 2 * PRICE_SCALE * budget,
+// the doubled trapezoid of `area`. None when the terms overflow u128.
+fn linear_tokens_for_cost(
+    initial_price: u128,
+    curve_slope: u128,
+    reserves: u64,
+    budget: u64,
+) -> Option<u128> {
+    let target = (2 * PRICE_SCALE).checked_mul(budget as u128)?;
+    let b = curve_slope
+        .checked_mul(reserves as u128)?
+        .checked_add(initial_price)?
+        .checked_mul(2)?;
+    if curve_slope == 0 {
+        return target.checked_div(b);
+    }
+    let discriminant = b
+        .checked_mul(b)?
+        .checked_add(curve_slope.checked_mul(4)?.checked_mul(target)?)?;
+    Some((sqrt(discriminant) - b) / (2 * curve_slope))
+}
+
+// Smaller root of slope * a^2 - 2 * (initial_price + slope * reserves) * a + 2 * PRICE_SCALE * target,
+// the doubled trapezoid of `area` below `reserves`. None when the terms overflow u128.
+fn linear_tokens_for_proceeds(
+    initial_price: u128,
+    curve_slope: u128,
+    reserves: u64,
+    target: u64,
+) -> Option<u128> {
+    let doubled = (2 * PRICE_SCALE).checked_mul(target as u128)?;
+    let b = curve_slope
+        .checked_mul(reserves as u128)?
+        .checked_add(initial_price)?
+        .checked_mul(2)?;
+    if curve_slope == 0 {
+        return Some(doubled.div_ceil(b));
+    }
+    let discriminant = b
+        .checked_mul(b)?
+        .checked_sub(curve_slope.checked_mul(4)?.checked_mul(doubled)?)?;
+    Some((b - sqrt(discriminant)).div_ceil(2 * curve_slope))
+}
+
+fn exponent(growth_rate: u128, reserves: u64) -> Result<u128> {
+    growth_rate
+        .checked_mul(reserves as u128)
+        .ok_or(error!(F44Code::MathOverflow))
+}
+
+fn invariant(virtual_f44_reserves: u64, virtual_token_reserves: u64) -> u128 {
+    virtual_f44_reserves as u128 * virtual_token_reserves as u128
+}
+
+fn remaining_tokens(virtual_token_reserves: u64, reserves: u64) -> Result<u128> {
+    let remaining = virtual_token_reserves
+        .checked_sub(reserves)
+        .ok_or(F44Code::InsufficientLiquidity)?;
+    require!(remaining > 0, F44Code::InsufficientLiquidity);
+    Ok(remaining as u128)
+}
+
+impl BondingCurve {
+    pub fn is_sol_quoted(&self) -> bool {
+        self.quote_mint == native_mint::ID
+    }
+
+    // Whether the graduation metric has passed the threshold.
+    pub fn graduated_by(&self, metric: GraduationMetric, threshold: u64) -> bool {
+        let progress = match metric {
+            GraduationMetric::MarketCap => self.current_mcap,
+            GraduationMetric::F44Raised => self.f44_reserves,
+            GraduationMetric::TokensSold => self.token_reserves,
+        };
+        progress > threshold
+    }
+
+    // Fewest tokens a buy needs for the curve to graduate, None when selling out the
+    // curve would not get there.
+    pub fn tokens_to_complete(&self) -> Result<Option<u64>> {
+        self.tokens_to_complete_by(self.graduation_metric, self.graduation_threshold)
+    }
+
+    pub fn tokens_to_complete_by(
+        &self,
+        metric: GraduationMetric,
+        threshold: u64,
+    ) -> Result<Option<u64>> {
+        let unsold = self
+            .token_total_supply
+            .checked_sub(self.token_reserves)
+            .ok_or(F44Code::InvalidReserves)?;
+        if unsold == 0 {
+            return Ok(None);
+        }
+
+        let amount = match metric {
+            GraduationMetric::MarketCap => {
+                let completes = |amount: u64| -> Result<bool> {
+                    let price = self.curve.price_at(self.token_reserves + amount)?;
+                    Ok(market_cap(price, self.token_total_supply)? > threshold)
+                };
+                if !completes(unsold)? {
+                    return Ok(None);
+                }
+
+                let (mut low, mut high) = (1u64, unsold);
+                while low < high {
+                    let mid = low + (high - low) / 2;
+                    if completes(mid)? {
+                        high = mid;
+                    } else {
+                        low = mid + 1;
+                    }
+                }
+                low
+            }
+            GraduationMetric::F44Raised => {
+                // one token past the most the remaining threshold pays for
+                let budget = threshold.saturating_sub(self.f44_reserves);
+                self.curve
+                    .tokens_for_cost(self.token_reserves, budget, unsold)?
+                    .saturating_add(1)
+            }
+            GraduationMetric::TokensSold => threshold
+                .saturating_sub(self.token_reserves)
+                .saturating_add(1),
+        };
+        Ok((amount <= unsold).then_some(amount))
+    }
+
+    // Refreshes the cached price and market cap after `token_reserves` changed.
+    pub fn update_price(&mut self) -> Result<()> {
+        self.current_price = self.curve.price_at(self.token_reserves)?;
+        self.current_mcap = market_cap(self.current_price, self.token_total_supply)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1B tokens with 6 decimals, priced in a 6 decimal quote
+    const SUPPLY: u64 = 1_000_000_000_000_000;
+
+    fn curves() -> Vec<CurveKind> {
+        vec![
+            CurveKind::Linear {
+                initial_price: 1_000_000_000_000,
+                curve_slope: 1_000,
+            },
+            CurveKind::Linear {
+                initial_price: 1_000_000_000_000,
+                curve_slope: 0,
+            },
+            CurveKind::Exponential {
+                initial_price: 1_000_000_000_000,
+                growth_rate: 1_000,
+            },
+            CurveKind::ConstantProduct {
+                virtual_f44_reserves: 30_000_000_000,
+                virtual_token_reserves: 1_073_000_000_000_000,
+            },
+        ]
+    }
+
+    // reserves spread over the curve, amounts from dust to a large chunk
+    const RESERVES: [u64; 4] = [0, 1_000_000, 400_000_000_000_000, SUPPLY - 1_000_000_000_000];
+    const AMOUNTS: [u64; 4] = [1, 999, 123_456_789, 1_000_000_000_000];
+
+    #[test]
+    fn curves_are_valid() {
+        for curve in curves() {
+            curve.validate(SUPPLY).unwrap();
+        }
+    }
+
+    #[test]
+    fn buying_rounds_up_and_selling_rounds_down() {
+        for curve in curves() {
+            for reserves in RESERVES {
+                for amount in AMOUNTS {
+                    let cost = curve.cost_to_buy(reserves, amount).unwrap();
+                    let exact = curve.area(reserves, reserves + amount, Rounding::Down).unwrap();
+                    assert!(cost >= exact);
+                    // a unit per rounded term, the constant product curve rounds two
+                    assert!(cost - exact <= 2, "{curve:?} {reserves} {amount}");
+                    // even one base unit of tokens is never free
+                    assert!(cost > 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_buy_then_sell_never_pays_out_more_than_it_took_in() {
+        for curve in curves() {
+            for reserves in RESERVES {
+                for amount in AMOUNTS {
+                    let cost = curve.cost_to_buy(reserves, amount).unwrap();
+                    let proceeds = curve.proceeds_from_sell(reserves + amount, amount).unwrap();
+                    assert!(proceeds <= cost, "{curve:?} {reserves} {amount}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn buying_in_pieces_costs_at_least_buying_at_once() {
+        for curve in curves() {
+            let whole = curve.cost_to_buy(1_000_000, 2_000_000).unwrap();
+            let pieces = curve.cost_to_buy(1_000_000, 1_000_000).unwrap()
+                + curve.cost_to_buy(2_000_000, 1_000_000).unwrap();
+            assert!(pieces >= whole);
+        }
+    }
+
+    #[test]
+    fn tokens_for_cost_is_the_most_the_budget_buys() {
+        for curve in curves() {
+            for reserves in RESERVES {
+                let max_amount = SUPPLY - reserves;
+                for budget in [1, 1_000, 1_000_000, 5_000_000_000] {
+                    let amount = curve.tokens_for_cost(reserves, budget, max_amount).unwrap();
+                    if amount > 0 {
+                        assert!(curve.cost_to_buy(reserves, amount).unwrap() <= budget);
+                    }
+                    if amount < max_amount {
+                        assert!(
+                            curve.cost_to_buy(reserves, amount + 1).unwrap() > budget,
+                            "{curve:?} {reserves} {budget}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tokens_for_proceeds_is_the_fewest_that_reach_the_target() {
+        for curve in curves() {
+            let reserves = 400_000_000_000_000;
+            let everything = curve.proceeds_from_sell(reserves, reserves).unwrap();
+            for target in [1, 1_000, everything / 3, everything] {
+                let amount = curve.tokens_for_proceeds(reserves, target).unwrap();
+                assert!(curve.proceeds_from_sell(reserves, amount).unwrap() >= target);
+                if amount > 1 {
+                    assert!(curve.proceeds_from_sell(reserves, amount - 1).unwrap() < target);
+                }
+            }
+            assert!(curve.tokens_for_proceeds(reserves, everything + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn prices_rise_along_the_curve() {
+        for curve in curves() {
+            let mut previous = curve.price_at(0).unwrap();
+            for reserves in RESERVES.iter().skip(1).copied().chain([SUPPLY]) {
+                let price = curve.price_at(reserves).unwrap();
+                assert!(price >= previous, "{curve:?} {reserves}");
+                previous = price;
+            }
+        }
+    }
+}
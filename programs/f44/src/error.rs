@@ -61,4 +61,118 @@ pub enum F44Code {
 
     #[msg("Invalid token reserves")]
     InvalidReserves,
-}
\ No newline at end of file
+
+    #[msg("The bonding curve account is not in the expected layout")]
+    InvalidCurveAccount,
+
+    #[msg("Token name, symbol or uri is empty or too long")]
+    InvalidMetadata,
+
+    #[msg("Metaplex metadata accounts are required for SPL Token mints")]
+    MetadataAccountsRequired,
+
+    #[msg("The vault received less F44 than required")]
+    InsufficientF44Received,
+
+    #[msg("The bonding curve liquidity has already been migrated")]
+    AlreadyMigrated,
+
+    #[msg("The AMM program does not match the configured one")]
+    InvalidAmmProgram,
+
+    #[msg("slippage: Too little output received from the swap")]
+    TooLittleOutput,
+
+    #[msg("slippage: Too much required to add the given liquidity")]
+    TooMuchLiquidityRequired,
+
+    #[msg("slippage: Too little received for the removed liquidity")]
+    TooLittleLiquidityReceived,
+
+    #[msg("Not enough liquidity shares")]
+    InsufficientShares,
+
+    #[msg("The F44 vault holds less than the curves, pools and protocol are owed")]
+    VaultBalanceMismatch,
+
+    #[msg("Fee basis points are above the allowed maximum")]
+    InvalidFeeBps,
+
+    #[msg("There are no creator fees to claim")]
+    NoCreatorFees,
+
+    #[msg("The referrer and referral accounts must be passed together and differ from the trader")]
+    InvalidReferrer,
+
+    #[msg("There are no referral rewards to claim")]
+    NoReferralRewards,
+
+    #[msg("slippage: Too few agent tokens received for the given amount of F44 Token.")]
+    TooFewTokensReceived,
+
+    #[msg("slippage: Too many agent tokens required to receive the given amount of F44 Token.")]
+    TooManyTokensRequired,
+
+    #[msg("The graduation threshold is zero or outside the allowed bounds")]
+    InvalidGraduationThreshold,
+
+    #[msg("The price oracle is missing or does not match the configured one")]
+    InvalidOracle,
+
+    #[msg("The quote asset is not accepted or does not match the bonding curve")]
+    InvalidQuoteMint,
+
+    #[msg("The proposed authority is not valid")]
+    InvalidAuthority,
+
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+
+    #[msg("Admin members must be unique and the threshold between one and their number")]
+    InvalidAdminSet,
+
+    #[msg("The signer is not a member of the admin set")]
+    NotAdminMember,
+
+    #[msg("The member already approved this action")]
+    ActionAlreadyApproved,
+
+    #[msg("The action was already executed")]
+    ActionAlreadyExecuted,
+
+    #[msg("The action does not have enough approvals")]
+    NotEnoughApprovals,
+
+    #[msg("The params delay is longer than allowed")]
+    InvalidParamsDelay,
+
+    #[msg("No params change is queued")]
+    NoPendingParams,
+
+    #[msg("The queued params change is still timelocked")]
+    ParamsTimelocked,
+
+    #[msg("Trading is paused")]
+    Paused,
+
+    #[msg("The pause reason is too long")]
+    InvalidPauseReason,
+
+    #[msg("The params are out of range or inconsistent")]
+    InvalidParams,
+
+    #[msg("Invalid global account")]
+    InvalidGlobalAccount,
+
+    #[msg("The admin action does not match the instruction it is used with")]
+    WrongAdminAction,
+
+    #[msg("The admin action was proposed before the admin members changed")]
+    StaleAdminAction,
+
+    #[msg("Only SOL and whitelisted quote curves are withdrawn, F44 curves migrate")]
+    NotWithdrawable,
+
+    #[msg("There are no protocol fees to claim")]
+    NoProtocolFees,
+}
@@ -1,24 +1,41 @@
 use anchor_lang::prelude::*;
 
+use crate::state::{AdminActionKind, CurveKind, GraduationMetric, Params};
+
 #[event]
 pub struct CreateEvent {
     pub mint: Pubkey,
     pub bonding_curve: Pubkey,
     pub user: Pubkey,
-    pub initial_price: f64,
+    pub curve: CurveKind,
+    pub creator_fee_bps: u64,
+    pub quote_mint: Pubkey,
+    pub graduation_metric: GraduationMetric,
+    pub graduation_threshold: u64,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
 }
 
 #[event]
 pub struct TradeEvent {
     pub mint: Pubkey,
+    // the F44 mint, or the native mint when `amount` and the fees are lamports
+    pub quote_mint: Pubkey,
     pub amount: u64,
+    // the filled amount, a buy that completes the curve can fill less than requested
     pub token_amount: u64,
+    pub requested_token_amount: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referral_fee: u64,
     pub is_buy: bool,
     pub user: Pubkey,
     pub timestamp: i64,
-    pub token_reserves: f64,
-    pub last_price: f64,
-    pub current_mcap: f64,
+    pub token_reserves: u64,
+    pub last_price: u128,
+    pub current_mcap: u64,
 }
 
 #[event]
@@ -30,11 +47,128 @@ pub struct CompleteEvent {
 }
 
 #[event]
-pub struct SetParamsEvent {
+pub struct MigrateEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub pool: Pubkey,
+    pub f44_amount: u64,
+    pub token_amount: u64,
+    pub lp_burned: u64,
+    pub timestamp: i64,
+}
+
+// a SOL or whitelisted quote curve settled by the owner wallet, `amount` is in its quote
+#[event]
+pub struct WithdrawEvent {
+    pub owner_wallet: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub quote_mint: Pubkey,
+    pub amount: u64,
+    pub token_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorFeeClaimed {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolFeeClaimed {
     pub fee_recipient: Pubkey,
-    pub initial_virtual_token_reserves: u64,
-    pub initial_virtual_sol_reserves: u64,
-    pub initial_real_token_reserves: u64,
-    pub token_total_supply: u64,
-    pub fee_basis_points: u64,
-}
\ No newline at end of file
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralClaimed {
+    pub referrer: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub is_buy: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub f44_reserves: u64,
+    pub token_reserves: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityEvent {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub is_add: bool,
+    pub shares: u64,
+    pub f44_amount: u64,
+    pub token_amount: u64,
+    pub lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionProposed {
+    pub action: Pubkey,
+    pub nonce: u64,
+    pub proposer: Pubkey,
+    pub kind: AdminActionKind,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionApproved {
+    pub action: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ActionExecuted {
+    pub action: Pubkey,
+    pub executor: Pubkey,
+    pub kind: AdminActionKind,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseEvent {
+    // None for the global switch
+    pub bonding_curve: Option<Pubkey>,
+    pub paused: bool,
+    pub sell_only: bool,
+    pub reason: String,
+    pub timestamp: i64,
+}
+
+// Emitted when a params change is queued and again when it is applied.
+#[event]
+pub struct SetParamsEvent {
+    pub old: Params,
+    pub new: Params,
+    pub effective_at: i64,
+    pub applied: bool,
+    pub timestamp: i64,
+}
@@ -0,0 +1,365 @@
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+use crate::{
+    constants::{
+        ADMIN_ACTION_SEED, ADMIN_SET_SEED, GLOBAL_STATE_SEED, MAX_ADMIN_MEMBERS,
+        MAX_PAUSE_REASON_LENGTH,
+    },
+    error::*,
+    events::*,
+    instructions::{
+        clear_pending_authority, clear_pending_params, pause_global, queue_params,
+        set_pending_authority, transfer_authority, update_oracle,
+    },
+    state::{AdminAction, AdminActionKind, AdminSet, Global},
+};
+
+#[derive(Accounts)]
+pub struct CreateAdminSet<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = authority @ F44Code::NotAuthorized
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [ADMIN_SET_SEED],
+        bump,
+        space = 8 + size_of::<AdminSet>()
+    )]
+    pub admin_set: Box<Account<'info, AdminSet>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    #[account(
+        mut,
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Box<Account<'info, AdminSet>>,
+
+    #[account(
+        init,
+        payer = member,
+        seeds = [ADMIN_ACTION_SEED, &admin_set.action_count.to_le_bytes()],
+        bump,
+        // room for the longest pause reason
+        space = 8 + size_of::<AdminAction>() + MAX_PAUSE_REASON_LENGTH
+    )]
+    pub action: Box<Account<'info, AdminAction>>,
+
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveAction<'info> {
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Box<Account<'info, AdminSet>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub action: Box<Account<'info, AdminAction>>,
+
+    pub member: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAction<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Box<Account<'info, AdminSet>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub action: Box<Account<'info, AdminAction>>,
+
+    pub member: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// The admin set only acts once the current authority proposed it and it executed
+// `AcceptAuthority`, from then on nobody can change the program alone.
+pub fn create_admin_set(ctx: Context<CreateAdminSet>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    let admin_set = &mut ctx.accounts.admin_set;
+    admin_set.action_count = 0;
+    set_members(admin_set, &members, threshold)?;
+
+    msg!(
+        "Admin set created - members: {:?}, threshold: {}",
+        members,
+        threshold,
+    );
+
+    Ok(())
+}
+
+// the proposer's approval is counted right away
+pub fn propose_action(ctx: Context<ProposeAction>, kind: AdminActionKind) -> Result<()> {
+    let accts = ctx.accounts;
+    let index = accts
+        .admin_set
+        .member_index(&accts.member.key())
+        .ok_or(F44Code::NotAdminMember)?;
+
+    let nonce = accts.admin_set.action_count;
+    accts.admin_set.action_count = nonce.checked_add(1).ok_or(F44Code::MathOverflow)?;
+
+    let action = &mut accts.action;
+    action.nonce = nonce;
+    action.proposer = accts.member.key();
+    action.action = kind;
+    action.approvals = 1 << index;
+    action.executed = false;
+
+    msg!(
+        "ActionProposed - action: {}, nonce: {}, proposer: {}, kind: {:?}, timestamp: {}",
+        action.key(),
+        nonce,
+        action.proposer,
+        action.action,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(ActionProposed {
+        action: action.key(),
+        nonce,
+        proposer: action.proposer,
+        kind: action.action.clone(),
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn approve_action(ctx: Context<ApproveAction>) -> Result<()> {
+    let accts = ctx.accounts;
+    let index = accts
+        .admin_set
+        .member_index(&accts.member.key())
+        .ok_or(F44Code::NotAdminMember)?;
+
+    let action = &mut accts.action;
+    require!(!action.executed, F44Code::ActionAlreadyExecuted);
+    require!(
+        action.nonce >= accts.admin_set.first_valid_action,
+        F44Code::StaleAdminAction
+    );
+    require!(action.approvals & (1 << index) == 0, F44Code::ActionAlreadyApproved);
+    action.approvals |= 1 << index;
+
+    let approvals = action.approvals.count_ones() as u8;
+    msg!(
+        "ActionApproved - action: {}, member: {}, approvals: {}, timestamp: {}",
+        action.key(),
+        accts.member.key(),
+        approvals,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(ActionApproved {
+        action: action.key(),
+        member: accts.member.key(),
+        approvals,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+    let accts = ctx.accounts;
+    let admin_set_key = accts.admin_set.key();
+    let timestamp = accts.clock.unix_timestamp;
+
+    use_action(&accts.admin_set, &mut accts.action, &accts.member.key())?;
+
+    // everything but taking over needs the admin set to be the authority already
+    let global = &mut accts.global;
+    let kind = &accts.action.action;
+    if !matches!(kind, AdminActionKind::AcceptAuthority) {
+        require!(global.authority == admin_set_key, F44Code::NotAuthorized);
+    }
+    match kind {
+        AdminActionKind::SetParams { update } => queue_params(global, update)?,
+        AdminActionKind::ProposeAuthority { new_authority } => {
+            set_pending_authority(global, *new_authority)?
+        }
+        AdminActionKind::AcceptAuthority => {
+            require!(global.pending_authority == admin_set_key, F44Code::NoPendingAuthority);
+            transfer_authority(global, admin_set_key, timestamp);
+        }
+        AdminActionKind::CancelAuthorityTransfer => clear_pending_authority(global)?,
+        AdminActionKind::CancelPendingParams => clear_pending_params(global)?,
+        AdminActionKind::DisableOracle => {
+            global.price_oracle = Pubkey::default();
+            global.usd_graduation_threshold = 0;
+            // a queued change must not switch it back on
+            global.pending_params.price_oracle = Pubkey::default();
+            global.pending_params.usd_graduation_threshold = 0;
+            msg!("Oracle disabled, curves graduate on their own thresholds");
+        }
+        AdminActionKind::SetGlobalPause {
+            paused,
+            sell_only,
+            reason,
+        } => pause_global(global, *paused, *sell_only, reason.clone(), timestamp)?,
+        AdminActionKind::SetOracle {
+            price_oracle,
+            usd_graduation_threshold,
+            oracle_max_age,
+            oracle_max_confidence_bps,
+        } => update_oracle(
+            global,
+            *price_oracle,
+            *usd_graduation_threshold,
+            *oracle_max_age,
+            *oracle_max_confidence_bps,
+        )?,
+        AdminActionKind::UpdateAdminSet {
+            members,
+            member_count,
+            threshold,
+        } => {
+            let members = members
+                .get(..*member_count as usize)
+                .ok_or(F44Code::InvalidAdminSet)?;
+            let admin_set = &mut accts.admin_set;
+            set_members(admin_set, members, *threshold)?;
+            admin_set.first_valid_action = admin_set.action_count;
+
+            msg!(
+                "Admin set updated - members: {:?}, threshold: {}",
+                members,
+                threshold,
+            );
+        }
+        // run through their own instructions, see `check_authority`
+        AdminActionKind::SetCurvePause { .. }
+        | AdminActionKind::PushPrice { .. }
+        | AdminActionKind::AddQuoteMint { .. }
+        | AdminActionKind::UpdateQuoteMint { .. }
+        | AdminActionKind::CancelQuoteMintUpdate { .. }
+        | AdminActionKind::UpgradeBondingCurve { .. } => {
+            return err!(F44Code::WrongAdminAction)
+        }
+    }
+
+    emit_executed(&accts.action, accts.member.key(), timestamp);
+
+    Ok(())
+}
+
+// Gate of the instructions that need the authority but take accounts of their own. Without
+// an admin action the authority has to sign. Once the admin set is the authority, any member
+// signs instead and passes the approved action describing exactly this call, which is used up.
+pub(crate) fn check_authority(
+    global: &Global,
+    signer: &Pubkey,
+    admin_set: Option<&Account<AdminSet>>,
+    admin_action: Option<&mut Account<AdminAction>>,
+    kind: AdminActionKind,
+) -> Result<()> {
+    let (admin_set, admin_action) = match (admin_set, admin_action) {
+        (Some(admin_set), Some(admin_action)) => (admin_set, admin_action),
+        _ => {
+            require!(global.authority == *signer, F44Code::NotAuthorized);
+            return Ok(());
+        }
+    };
+    require!(global.authority == admin_set.key(), F44Code::NotAuthorized);
+    require!(admin_action.action == kind, F44Code::WrongAdminAction);
+
+    use_action(admin_set, admin_action, signer)?;
+    emit_executed(admin_action, *signer, Clock::get()?.unix_timestamp);
+
+    Ok(())
+}
+
+fn set_members(admin_set: &mut AdminSet, members: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !members.is_empty() && members.len() <= MAX_ADMIN_MEMBERS,
+        F44Code::InvalidAdminSet
+    );
+    require!(
+        threshold > 0 && threshold as usize <= members.len(),
+        F44Code::InvalidAdminSet
+    );
+    for (i, member) in members.iter().enumerate() {
+        require!(!members[..i].contains(member), F44Code::InvalidAdminSet);
+    }
+
+    admin_set.members = [Pubkey::default(); MAX_ADMIN_MEMBERS];
+    admin_set.members[..members.len()].copy_from_slice(members);
+    admin_set.member_count = members.len() as u8;
+    admin_set.threshold = threshold;
+
+    Ok(())
+}
+
+// Checks a member may execute the action and marks it executed.
+fn use_action(admin_set: &AdminSet, action: &mut AdminAction, member: &Pubkey) -> Result<()> {
+    admin_set
+        .member_index(member)
+        .ok_or(F44Code::NotAdminMember)?;
+    require!(!action.executed, F44Code::ActionAlreadyExecuted);
+    require!(
+        action.nonce >= admin_set.first_valid_action,
+        F44Code::StaleAdminAction
+    );
+    require!(
+        action.approvals.count_ones() >= admin_set.threshold as u32,
+        F44Code::NotEnoughApprovals
+    );
+    action.executed = true;
+
+    Ok(())
+}
+
+fn emit_executed(action: &Account<AdminAction>, executor: Pubkey, timestamp: i64) {
+    msg!(
+        "ActionExecuted - action: {}, executor: {}, kind: {:?}, timestamp: {}",
+        action.key(),
+        executor,
+        action.action,
+        timestamp,
+    );
+
+    emit!(ActionExecuted {
+        action: action.key(),
+        executor,
+        kind: action.action.clone(),
+        timestamp,
+    });
+}
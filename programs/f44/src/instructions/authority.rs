@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::GLOBAL_STATE_SEED,
+    error::*,
+    events::*,
+    state::Global,
+};
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = authority @ F44Code::NotAuthorized
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        constraint = global.pending_authority != Pubkey::default() @ F44Code::NoPendingAuthority,
+        constraint = global.pending_authority == new_authority.key() @ F44Code::NotAuthorized
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub new_authority: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// The authority only changes once the proposed key signs `accept_authority`, so a typo
+// cannot hand the program to a key nobody holds. A new proposal replaces the pending one.
+pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+    set_pending_authority(&mut ctx.accounts.global, new_authority)
+}
+
+pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let accts = ctx.accounts;
+    transfer_authority(&mut accts.global, accts.new_authority.key(), accts.clock.unix_timestamp);
+    Ok(())
+}
+
+pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+    clear_pending_authority(&mut ctx.accounts.global)
+}
+
+// The helpers below are shared with the admin multisig, callers check who may act.
+pub(crate) fn set_pending_authority(global: &mut Global, new_authority: Pubkey) -> Result<()> {
+    require!(new_authority != Pubkey::default(), F44Code::InvalidAuthority);
+
+    global.pending_authority = new_authority;
+
+    msg!(
+        "Authority transfer proposed - authority: {}, pendingAuthority: {}",
+        global.authority,
+        global.pending_authority,
+    );
+
+    Ok(())
+}
+
+pub(crate) fn transfer_authority(global: &mut Global, new_authority: Pubkey, timestamp: i64) {
+    let previous_authority = global.authority;
+    global.authority = new_authority;
+    global.pending_authority = Pubkey::default();
+
+    msg!(
+        "AuthorityTransferred - previousAuthority: {}, newAuthority: {}, timestamp: {}",
+        previous_authority,
+        new_authority,
+        timestamp,
+    );
+
+    emit!(AuthorityTransferred {
+        previous_authority,
+        new_authority,
+        timestamp,
+    });
+}
+
+pub(crate) fn clear_pending_authority(global: &mut Global) -> Result<()> {
+    require!(
+        global.pending_authority != Pubkey::default(),
+        F44Code::NoPendingAuthority
+    );
+    msg!(
+        "Authority transfer cancelled - authority: {}, pendingAuthority: {}",
+        global.authority,
+        global.pending_authority,
+    );
+    global.pending_authority = Pubkey::default();
+
+    Ok(())
+}
@@ -2,14 +2,25 @@ use anchor_lang::prelude::*;
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer, Mint, Token, TokenAccount, Transfer},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
+use std::mem::size_of;
+
 use crate::{
-    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED},
+    constants::{
+        BONDING_CURVE, BPS_DENOMINATOR, F44_VAULT_SEED, GLOBAL_STATE_SEED, POOL_RESERVE_BPS,
+        POOL_SEED, QUOTE_CONFIG_SEED, REFERRAL_SEED,
+    },
     error::*,
     events::*,
-    state::{BondingCurve, Global},
+    math::{bps_fee, mul_div, to_u64, Rounding},
+    oracle::OraclePrice,
+    state::{BondingCurve, Global, GraduationMetric, Pool, QuoteConfig, Referral},
+    utils::{
+        amount_with_fee, check_not_paused, check_quote_config, check_quote_vault,
+        create_pda_account, credit_referral, quote_supply, referral_share, transfer_fee,
+    },
 };
 
 #[derive(Accounts)]
@@ -21,8 +32,11 @@ pub struct Buy<'info> {
     )]
     pub global: Box<Account<'info, Global>>,
 
-    #[account(mut)]
-    pub mint: Box<Account<'info, Mint>>, // the mint address of token
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>, // the mint address of token
 
     #[account(
         mut,
@@ -35,127 +49,437 @@ pub struct Buy<'info> {
         mut,
         token::mint = mint,
         token::authority = bonding_curve,
+        token::token_program = token_program,
     )]
-    pub associated_bonding_curve: Box<Account<'info, TokenAccount>>,
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub associated_user: Box<Account<'info, TokenAccount>>,
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub f44_mint: Box<Account<'info, Mint>>,
+    // the curve's quote mint, F44 or a whitelisted mint
+    #[account(
+        mut,
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == bonding_curve.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
         bump,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // vault accounting of a whitelisted quote mint, only for curves quoted in one
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, f44_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_config: Option<Box<Account<'info, QuoteConfig>>>,
 
     #[account(
         mut,
         associated_token::mint = f44_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = f44_token_program,
     )]
-    pub associated_user_f44_account: Box<Account<'info, TokenAccount>>,
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = f44_mint,
+        token::token_program = f44_token_program,
+        constraint = fee_recipient.owner == global.fee_recipient @ F44Code::UnValidFeeRecipient
+    )]
+    pub fee_recipient: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: any wallet that routed the trade, only used as the seed of its referral account
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [REFERRAL_SEED, referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+        space = 8 + size_of::<Referral>()
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+
+    /// CHECK: checked against `global.price_oracle` and parsed by `OraclePrice::load`
+    #[account(
+        constraint = price_oracle.key() == global.price_oracle @ F44Code::InvalidOracle
+    )]
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: the built-in pool, only created when this buy completes the curve
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub pool: UncheckedAccount<'info>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub clock: Sysvar<'info, Clock>,
 }
+// What buying `amount` agent tokens costs, fees included.
+pub(crate) struct BuyCost {
+    pub f44_cost: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+    // the vault and the fee recipient have to receive the full amounts, so transfer-fee F44 mints are grossed up
+    pub f44_transfer_amount: u64,
+    pub fee_transfer_amount: u64,
+}
+
+impl BuyCost {
+    pub fn total(&self) -> Result<u64> {
+        self.f44_transfer_amount
+            .checked_add(self.fee_transfer_amount)
+            .ok_or(error!(F44Code::MathOverflow))
+    }
+}
+
+pub(crate) fn buy_cost(
+    global: &Global,
+    bonding_curve: &BondingCurve,
+    // None for SOL curves, lamports have no transfer fee
+    quote_mint_info: Option<&AccountInfo>,
+    referred: bool,
+    amount: u64,
+) -> Result<BuyCost> {
+    let with_fee = |amount: u64| match quote_mint_info {
+        Some(info) => amount_with_fee(info, amount),
+        None => Ok(amount),
+    };
+    // Calculate F44 cost, rounded up in favour of the curve
+    let f44_cost = bonding_curve
+        .curve
+        .cost_to_buy(bonding_curve.token_reserves, amount)?;
+    // the trading and creator fees are charged on top of the cost
+    let fee = bps_fee(f44_cost, global.trade_fee_bps)?;
+    let creator_fee = bps_fee(f44_cost, bonding_curve.creator_fee_bps)?;
+    // a referrer's share comes out of the trading fee
+    let referral_fee = if referred { referral_share(global, fee)? } else { 0 };
+    // the creator and referral fees are held in the vault with the cost
+    let vault_amount = f44_cost
+        .checked_add(creator_fee)
+        .and_then(|v| v.checked_add(referral_fee))
+        .ok_or(F44Code::MathOverflow)?;
+    let protocol_fee = fee - referral_fee;
+
+    Ok(BuyCost {
+        f44_cost,
+        fee,
+        creator_fee,
+        referral_fee,
+        f44_transfer_amount: with_fee(vault_amount)?,
+        fee_transfer_amount: if protocol_fee > 0 { with_fee(protocol_fee)? } else { 0 },
+    })
+}
+
+// With an oracle configured an F44 curve graduates on the USD value of the F44 it raised.
+// When the price is stale or too uncertain the curve's own threshold applies.
+pub(crate) fn graduation(
+    global: &Global,
+    bonding_curve: &BondingCurve,
+    price_oracle: Option<&AccountInfo>,
+    f44_decimals: u8,
+    now: i64,
+) -> Result<(GraduationMetric, u64)> {
+    let fallback = (
+        bonding_curve.graduation_metric,
+        bonding_curve.graduation_threshold,
+    );
+    // the oracle prices F44, so curves in SOL or a whitelisted mint keep their own threshold
+    if global.price_oracle == Pubkey::default()
+        || global.usd_graduation_threshold == 0
+        || bonding_curve.quote_mint != global.f44_mint
+    {
+        return Ok(fallback);
+    }
+    let oracle = price_oracle.ok_or(F44Code::InvalidOracle)?;
+
+    let threshold = OraclePrice::load(oracle)
+        .and_then(|price| {
+            price.checked(
+                now,
+                global.oracle_max_age,
+                global.oracle_max_confidence_bps,
+            )
+        })
+        .and_then(|price| price.f44_for_usd(global.usd_graduation_threshold, f44_decimals));
+    match threshold {
+        Some(threshold) => Ok((GraduationMetric::F44Raised, threshold)),
+        None => {
+            msg!("Oracle price unavailable, graduating on the curve threshold");
+            Ok(fallback)
+        }
+    }
+}
+
+// Most tokens one buy can fill: the buy that completes the curve stops at the
+// threshold, and no buy takes the pool's reserve out of the vault.
+pub(crate) fn max_fill(
+    bonding_curve: &BondingCurve,
+    vault_amount: u64,
+    (metric, threshold): (GraduationMetric, u64),
+) -> Result<u64> {
+    let sellable = vault_amount.saturating_sub(pool_reserve(bonding_curve)?);
+    Ok(match bonding_curve.tokens_to_complete_by(metric, threshold)? {
+        Some(amount) => amount.min(sellable),
+        None => sellable,
+    })
+}
+
+// Whether the curve is complete once its vault is down to `vault_amount`. Selling out
+// completes it whatever the threshold, otherwise a threshold past the sellable supply
+// would leave it trading forever.
+pub(crate) fn completes(
+    bonding_curve: &BondingCurve,
+    vault_amount: u64,
+    (metric, threshold): (GraduationMetric, u64),
+) -> Result<bool> {
+    Ok(bonding_curve.graduated_by(metric, threshold)
+        || vault_amount <= pool_reserve(bonding_curve)?)
+}
+
+fn pool_reserve(bonding_curve: &BondingCurve) -> Result<u64> {
+    bps_fee(bonding_curve.token_total_supply, POOL_RESERVE_BPS)
+}
+
+impl<'info> Buy<'info> {
+    fn graduation(&self) -> Result<(GraduationMetric, u64)> {
+        graduation(
+            &self.global,
+            &self.bonding_curve,
+            self.price_oracle.as_ref().map(|o| o.to_account_info()).as_ref(),
+            self.f44_mint.decimals,
+            self.clock.unix_timestamp,
+        )
+    }
+
+    fn max_fill(&self, graduation: (GraduationMetric, u64)) -> Result<u64> {
+        max_fill(
+            &self.bonding_curve,
+            self.associated_bonding_curve.amount,
+            graduation,
+        )
+    }
+
+    fn cost(&self, amount: u64) -> Result<BuyCost> {
+        buy_cost(
+            &self.global,
+            &self.bonding_curve,
+            Some(&self.f44_mint.to_account_info()),
+            self.referral.is_some(),
+            amount,
+        )
+    }
+}
+
 // amount is the agent token amount
 // max_f44_amount is the max f44 token amount that will be used as payment
 pub fn buy(ctx: Context<Buy>, amount: u64, max_f44_amount: u64) -> Result<()> {
-    let accts = ctx.accounts;
-
     // Basic parameter validation
-    require!(amount > 0, F44Code::ZeroAmount);
     require!(max_f44_amount > 0, F44Code::ZeroAmount);
-    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
 
-    let bonding_curve = &accts.bonding_curve;
-    let decimals = accts.mint.decimals;
-    let f44_decimals = accts.f44_mint.decimals;
+    process_buy(ctx.accounts, ctx.bumps.pool, amount, max_f44_amount)
+}
 
-    // Calculate F44 cost with input validation
-    let token_amount = amount as f64 / 10_u64.pow(decimals.into()) as f64;
-    let f44_amount = calculate_f44_cost(bonding_curve, token_amount)?;
-    accts.bonding_curve.current_price = bonding_curve.curve_slope
-        * (bonding_curve.token_reserves + token_amount)
-        + bonding_curve.initial_price;
+// f44_in is the F44 amount to spend, fees included
+// min_tokens_out is the least agent token amount it has to buy
+pub fn buy_exact_in(ctx: Context<Buy>, f44_in: u64, min_tokens_out: u64) -> Result<()> {
+    let accts = ctx.accounts;
 
-    // Convert to token units with overflow protection
-    let f44_transfer_amount = (f44_amount * 10_u64.pow(f44_decimals.into()) as f64) as u64;
+    require!(f44_in > 0, F44Code::ZeroAmount);
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
 
-    // Validate transfer amount
-    require!(f44_transfer_amount > 0, F44Code::InvalidAmount);
+    // Start from the input net of fees, then tighten the budget until the whole buy fits in f44_in
+    let f44_net = f44_in - transfer_fee(&accts.f44_mint.to_account_info(), f44_in)?;
+    let fee_bps = BPS_DENOMINATOR
+        .checked_add(accts.global.trade_fee_bps)
+        .and_then(|v| v.checked_add(accts.bonding_curve.creator_fee_bps))
+        .ok_or(F44Code::MathOverflow)?;
+    let mut budget = to_u64(mul_div(
+        f44_net as u128,
+        BPS_DENOMINATOR as u128,
+        fee_bps as u128,
+        Rounding::Down,
+    )?)?;
+    let max_fill = accts.max_fill(accts.graduation()?)?;
+    let amount = loop {
+        let amount = accts.bonding_curve.curve.tokens_for_cost(
+            accts.bonding_curve.token_reserves,
+            budget,
+            max_fill,
+        )?;
+        if amount == 0 {
+            break 0;
+        }
+        let total = accts.cost(amount)?.total()?;
+        if total <= f44_in {
+            break amount;
+        }
+        budget = budget.saturating_sub(total - f44_in);
+    };
+    require!(amount >= min_tokens_out, F44Code::TooFewTokensReceived);
+
+    process_buy(accts, ctx.bumps.pool, amount, f44_in)
+}
+
+fn process_buy(
+    accts: &mut Buy,
+    pool_bump: u8,
+    requested_amount: u64,
+    max_f44_amount: u64,
+) -> Result<()> {
+    // Basic parameter validation
+    require!(requested_amount > 0, F44Code::ZeroAmount);
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
+    check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+    check_quote_config(
+        &accts.global,
+        &accts.bonding_curve,
+        accts.quote_config.as_deref().map(|c| &**c),
+    )?;
+    // referral rewards are only paid in F44
     require!(
-        f44_transfer_amount <= max_f44_amount,
-        F44Code::TooMuchF44Required
+        accts.quote_config.is_none() || accts.referral.is_none(),
+        F44Code::InvalidReferrer
     );
 
-    // Check bonding curve has sufficient tokens
+    // only the filled part is charged, so nothing has to be refunded
+    let graduation = accts.graduation()?;
+    let amount = requested_amount.min(accts.max_fill(graduation)?);
+    require!(amount > 0, F44Code::InsufficientLiquidity);
+
+    let BuyCost {
+        f44_cost,
+        creator_fee,
+        referral_fee,
+        f44_transfer_amount,
+        fee_transfer_amount,
+        ..
+    } = accts.cost(amount)?;
+    let referrer = accts.referrer.as_ref().map(|r| r.key());
+    credit_referral(
+        accts.user.key(),
+        referrer,
+        accts.referral.as_deref_mut().map(|r| &mut **r),
+        referral_fee,
+    )?;
+
+    // Validate transfer amount, fee included
+    require!(f44_transfer_amount > 0, F44Code::InvalidAmount);
     require!(
-        accts.associated_bonding_curve.amount >= amount,
-        F44Code::InsufficientLiquidity
+        f44_transfer_amount
+            .checked_add(fee_transfer_amount)
+            .ok_or(F44Code::MathOverflow)?
+            <= max_f44_amount,
+        F44Code::TooMuchF44Required
     );
 
     // send f44 token to the f44 reserve pool
+    let vault_before = accts.f44_vault.amount;
     let cpi_ctx = CpiContext::new(
-        accts.token_program.to_account_info(),
-        Transfer {
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
             from: accts.associated_user_f44_account.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
             to: accts.f44_vault.to_account_info().clone(),
             authority: accts.user.to_account_info().clone(),
         },
     );
-    transfer(cpi_ctx, f44_transfer_amount)?;
+    transfer_checked(cpi_ctx, f44_transfer_amount, accts.f44_mint.decimals)?;
 
-    // Update F44 supply with overflow check
-    accts.global.f44_supply = accts
-        .global
-        .f44_supply
-        .checked_add(f44_transfer_amount)
+    // Credit what the vault actually received
+    accts.f44_vault.reload()?;
+    let f44_received = accts.f44_vault.amount - vault_before;
+    require!(
+        f44_received >= f44_cost + creator_fee + referral_fee,
+        F44Code::InsufficientF44Received
+    );
+    accts.bonding_curve.f44_reserves = accts
+        .bonding_curve
+        .f44_reserves
+        .checked_add(f44_received - creator_fee - referral_fee)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.bonding_curve.creator_fees = accts
+        .bonding_curve
+        .creator_fees
+        .checked_add(creator_fee)
         .ok_or(F44Code::MathOverflow)?;
+    let supply = quote_supply(&mut accts.global, accts.quote_config.as_deref_mut().map(|c| &mut **c));
+    *supply = supply
+        .checked_add(f44_received)
+        .ok_or(F44Code::MathOverflow)?;
+    check_quote_vault(
+        &mut accts.global,
+        accts.quote_config.as_deref_mut().map(|c| &mut **c),
+        accts.f44_vault.amount,
+    )?;
+
+    // send the trading fee to the fee recipient
+    if fee_transfer_amount > 0 {
+        let cpi_ctx = CpiContext::new(
+            accts.f44_token_program.to_account_info(),
+            TransferChecked {
+                from: accts.associated_user_f44_account.to_account_info().clone(),
+                mint: accts.f44_mint.to_account_info().clone(),
+                to: accts.fee_recipient.to_account_info().clone(),
+                authority: accts.user.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx, fee_transfer_amount, accts.f44_mint.decimals)?;
+    }
 
     // send token from agent token vault account to user
     let binding = accts.mint.key();
 
-    let (_, bump) =
-        Pubkey::find_program_address(&[BONDING_CURVE, binding.as_ref()], ctx.program_id);
+    let (_, bump) = Pubkey::find_program_address(&[BONDING_CURVE, binding.as_ref()], &crate::ID);
     let vault_seeds = &[BONDING_CURVE, binding.as_ref(), &[bump]];
     let signer = &[&vault_seeds[..]];
 
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: accts.associated_bonding_curve.to_account_info().clone(),
+            mint: accts.mint.to_account_info().clone(),
             to: accts.associated_user.to_account_info().clone(),
             authority: accts.bonding_curve.to_account_info().clone(),
         },
     );
-    transfer(cpi_ctx.with_signer(signer), amount)?;
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.mint.decimals)?;
 
     // Update bonding curve state
-    let token_amount = amount as f64 / 10_u64.pow(decimals.into()) as f64;
-    accts.bonding_curve.token_reserves += token_amount;
-
-    // Calculate market cap
-    let macp = (accts.bonding_curve.curve_slope * accts.bonding_curve.token_reserves
-        + accts.bonding_curve.initial_price)
-        * accts.bonding_curve.token_total_supply;
-    accts.bonding_curve.current_mcap = macp;
+    let bonding_curve = &mut accts.bonding_curve;
+    bonding_curve.token_reserves = bonding_curve
+        .token_reserves
+        .checked_add(amount)
+        .ok_or(F44Code::MathOverflow)?;
+    bonding_curve.update_price()?;
+    let macp = bonding_curve.current_mcap;
 
     msg!("Current market cap is {}", macp);
 
-    if macp > accts.bonding_curve.mcap_limit {
+    // sells burn tokens, so only the vault knows how many are left
+    accts.associated_bonding_curve.reload()?;
+    if completes(&accts.bonding_curve, accts.associated_bonding_curve.amount, graduation)? {
         accts.bonding_curve.complete = true;
 
         msg!(
@@ -172,16 +496,27 @@ pub fn buy(ctx: Context<Buy>, amount: u64, max_f44_amount: u64) -> Result<()> {
             bonding_curve: accts.bonding_curve.key(),
             timestamp: accts.clock.unix_timestamp,
         });
+
+        // without an external AMM an F44 curve graduates straight into the built-in pool,
+        // curves in other quote mints are wound down by `withdraw`
+        if accts.global.amm_program == Pubkey::default() && accts.quote_config.is_none() {
+            seed_pool(accts, pool_bump)?;
+        }
     }
 
     msg!(
-        "TradeEvent - type: Buy, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, f44Amount: {}, tokenAmount: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
+        "TradeEvent - type: Buy, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, f44Amount: {}, tokenAmount: {}, requestedTokenAmount: {}, fee: {}, creatorFee: {}, referrer: {:?}, referralFee: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
         accts.user.key(),
         accts.mint.key(),
         accts.bonding_curve.key(),
         accts.clock.unix_timestamp,
-        f44_amount,
+        f44_transfer_amount,
         amount,
+        requested_amount,
+        fee_transfer_amount,
+        creator_fee,
+        referrer,
+        referral_fee,
         true,
         accts.bonding_curve.token_reserves,
         accts.bonding_curve.current_price,
@@ -190,8 +525,14 @@ pub fn buy(ctx: Context<Buy>, amount: u64, max_f44_amount: u64) -> Result<()> {
 
     emit!(TradeEvent {
         mint: accts.mint.key(),
+        quote_mint: accts.f44_mint.key(),
         amount: f44_transfer_amount,
         token_amount: amount,
+        requested_token_amount: requested_amount,
+        fee: fee_transfer_amount,
+        creator_fee,
+        referrer,
+        referral_fee,
         is_buy: true,
         user: accts.user.key(),
         timestamp: accts.clock.unix_timestamp,
@@ -203,54 +544,135 @@ pub fn buy(ctx: Context<Buy>, amount: u64, max_f44_amount: u64) -> Result<()> {
     Ok(())
 }
 
-fn calculate_f44_cost(bonding_curve: &Account<BondingCurve>, token_amount: f64) -> Result<f64> {
-    // Input validation
-    require!(token_amount >= 0.0, F44Code::InvalidAmount);
-    require!(token_amount.is_finite(), F44Code::InvalidAmount);
-    require!(bonding_curve.curve_slope >= 0.0, F44Code::InvalidSlope);
-    require!(bonding_curve.curve_slope.is_finite(), F44Code::InvalidSlope);
-    require!(bonding_curve.initial_price >= 0.0, F44Code::InvalidPrice);
-    require!(
-        bonding_curve.initial_price.is_finite(),
-        F44Code::InvalidPrice
-    );
-    require!(
-        bonding_curve.token_reserves >= 0.0,
-        F44Code::InvalidReserves
-    );
-    require!(
-        bonding_curve.token_reserves.is_finite(),
-        F44Code::InvalidReserves
+// Turns the curve's proceeds and unsold tokens into the reserves of the built-in pool.
+// Both stay in the vaults they are already in, so only the bookkeeping moves.
+fn seed_pool(accts: &mut Buy, pool_bump: u8) -> Result<()> {
+    let f44_raised = accts.bonding_curve.f44_reserves;
+    // at least the pool's reserve, `max_fill` never sells it
+    let token_amount = accts.associated_bonding_curve.amount;
+
+    let mint = accts.mint.key();
+    let pool_seeds: &[&[u8]] = &[POOL_SEED, mint.as_ref(), &[pool_bump]];
+    let pool_info = accts.pool.to_account_info();
+    create_pda_account(
+        &accts.user.to_account_info(),
+        &pool_info,
+        &accts.system_program.to_account_info(),
+        8 + size_of::<Pool>(),
+        &crate::ID,
+        pool_seeds,
+    )?;
+
+    let mut pool = Pool {
+        token_mint: mint,
+        quote_mint: Pubkey::default(),
+        f44_reserves: 0,
+        token_reserves: 0,
+        lp_supply: 0,
+        locked_lp: 0,
+    };
+    pool.seed(mint, accts.bonding_curve.quote_mint, f44_raised, token_amount)?;
+    pool.try_serialize(&mut &mut pool_info.try_borrow_mut_data()?[..])?;
+
+    // the F44 now belongs to the pool and stays counted in `f44_supply`
+    accts.bonding_curve.f44_reserves = 0;
+    accts.bonding_curve.migrated = true;
+
+    msg!(
+        "MigrateEvent - user: {}, mint: {}, bondingCurve: {}, pool: {}, f44Amount: {}, tokenAmount: {}, lpBurned: {}, timestamp: {}",
+        accts.user.key(),
+        mint,
+        accts.bonding_curve.key(),
+        pool_info.key(),
+        f44_raised,
+        token_amount,
+        pool.locked_lp,
+        accts.clock.unix_timestamp
     );
 
-    // Price calculations
-    let first_price =
-        bonding_curve.curve_slope * bonding_curve.token_reserves + bonding_curve.initial_price;
-    let last_price = bonding_curve.curve_slope * (bonding_curve.token_reserves + token_amount)
-        + bonding_curve.initial_price;
+    emit!(MigrateEvent {
+        user: accts.user.key(),
+        mint,
+        bonding_curve: accts.bonding_curve.key(),
+        pool: pool_info.key(),
+        f44_amount: f44_raised,
+        token_amount,
+        lp_burned: pool.locked_lp,
+        timestamp: accts.clock.unix_timestamp,
+    });
 
-    // Validate calculated prices
-    require!(first_price >= 0.0, F44Code::InvalidCalculation);
-    require!(first_price.is_finite(), F44Code::InvalidCalculation);
-    require!(last_price >= 0.0, F44Code::InvalidCalculation);
-    require!(last_price.is_finite(), F44Code::InvalidCalculation);
+    Ok(())
+}
 
-    msg!(
-        "The first price is {} and last price is {}",
-        first_price,
-        last_price
-    );
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::CurveKind;
+
+    // 1B tokens with 6 decimals, 1% of them kept for the pool
+    const SUPPLY: u64 = 1_000_000_000_000_000;
+    const RESERVE: u64 = SUPPLY / 100;
+
+    fn curve(token_reserves: u64) -> BondingCurve {
+        let mut curve = BondingCurve {
+            curve: CurveKind::Linear {
+                initial_price: 1_000_000_000_000,
+                curve_slope: 1_000,
+            },
+            token_reserves,
+            token_total_supply: SUPPLY,
+            token_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            f44_reserves: 0,
+            graduation_metric: GraduationMetric::TokensSold,
+            graduation_threshold: SUPPLY - 1,
+            current_price: 0,
+            current_mcap: 0,
+            complete: false,
+            migrated: false,
+            creator: Pubkey::default(),
+            creator_fee_bps: 0,
+            creator_fees: 0,
+            protocol_fees: 0,
+            paused: false,
+            sell_only: false,
+        };
+        curve.update_price().unwrap();
+        curve
+    }
 
-    let average_price = (first_price + last_price) / 2.0;
-    msg!("The average price of token is {}", average_price);
+    #[test]
+    fn a_threshold_past_the_sellable_supply_completes_on_selling_out() {
+        let graduation = (GraduationMetric::TokensSold, SUPPLY - 1);
+        let fill = max_fill(&curve(0), SUPPLY, graduation).unwrap();
+        assert_eq!(fill, SUPPLY - RESERVE);
 
-    let f44_amount = token_amount * average_price;
-    msg!("The F44 amount is {}", f44_amount);
+        let after = curve(fill);
+        assert!(!after.graduated_by(graduation.0, graduation.1));
+        assert!(completes(&after, SUPPLY - fill, graduation).unwrap());
+        assert!(!completes(&curve(fill - 1), SUPPLY - fill + 1, graduation).unwrap());
+    }
 
-    // Validate final result
-    require!(f44_amount >= 0.0, F44Code::InvalidCalculation);
-    require!(f44_amount.is_finite(), F44Code::InvalidCalculation);
-    require!(f44_amount <= f64::MAX / 2.0, F44Code::InvalidCalculation); // Prevent potential overflow
+    #[test]
+    fn the_last_tokens_before_the_reserve_are_clipped() {
+        let graduation = (GraduationMetric::MarketCap, u64::MAX);
+        let sold = SUPPLY - RESERVE - 5;
+        assert_eq!(max_fill(&curve(sold), RESERVE + 5, graduation).unwrap(), 5);
+        assert_eq!(max_fill(&curve(sold + 5), RESERVE, graduation).unwrap(), 0);
+    }
 
-    Ok(f44_amount)
+    #[test]
+    fn a_sold_out_curve_still_seeds_the_pool() {
+        let mut pool = Pool {
+            token_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            f44_reserves: 0,
+            token_reserves: 0,
+            lp_supply: 0,
+            locked_lp: 0,
+        };
+        pool.seed(Pubkey::default(), Pubkey::default(), 1, RESERVE).unwrap();
+        assert_eq!(pool.token_reserves, RESERVE);
+        assert!(pool.locked_lp > 0);
+    }
 }
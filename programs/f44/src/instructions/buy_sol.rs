@@ -0,0 +1,276 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BONDING_CURVE, BPS_DENOMINATOR, GLOBAL_STATE_SEED, SOL_VAULT_SEED},
+    error::*,
+    events::*,
+    instructions::{buy_cost, completes, max_fill, BuyCost},
+    math::{mul_div, to_u64, Rounding},
+    state::{BondingCurve, Global, GraduationMetric},
+    utils::{check_not_paused, check_sol_vault, transfer_lamports},
+};
+
+// Buy on a SOL curve. Lamports go from the user to the curve's SOL vault, the trading fee
+// included, which stays there until the fee recipient claims it. Referral rewards are F44
+// credits paid out of the F44 vault, so SOL trades take no referrer.
+#[derive(Accounts)]
+pub struct BuySol<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_sol_quoted() @ F44Code::InvalidQuoteMint
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve,
+        token::token_program = token_program,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+impl<'info> BuySol<'info> {
+    // the oracle prices F44, so SOL curves always graduate on their own threshold
+    fn graduation(&self) -> (GraduationMetric, u64) {
+        (
+            self.bonding_curve.graduation_metric,
+            self.bonding_curve.graduation_threshold,
+        )
+    }
+
+    fn max_fill(&self) -> Result<u64> {
+        max_fill(
+            &self.bonding_curve,
+            self.associated_bonding_curve.amount,
+            self.graduation(),
+        )
+    }
+}
+
+// amount is the agent token amount
+// max_sol_amount is the most lamports that will be paid, fees included
+pub fn buy_sol(ctx: Context<BuySol>, amount: u64, max_sol_amount: u64) -> Result<()> {
+    require!(max_sol_amount > 0, F44Code::ZeroAmount);
+
+    process_buy_sol(ctx.accounts, ctx.bumps.bonding_curve, amount, max_sol_amount)
+}
+
+// sol_in is the lamports to spend, fees included
+// min_tokens_out is the least agent token amount it has to buy
+pub fn buy_sol_exact_in(ctx: Context<BuySol>, sol_in: u64, min_tokens_out: u64) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(sol_in > 0, F44Code::ZeroAmount);
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
+
+    // Start from the input net of fees, then tighten the budget until the whole buy fits in sol_in
+    let fee_bps = BPS_DENOMINATOR
+        .checked_add(accts.global.trade_fee_bps)
+        .and_then(|v| v.checked_add(accts.bonding_curve.creator_fee_bps))
+        .ok_or(F44Code::MathOverflow)?;
+    let mut budget = to_u64(mul_div(
+        sol_in as u128,
+        BPS_DENOMINATOR as u128,
+        fee_bps as u128,
+        Rounding::Down,
+    )?)?;
+    let max_fill = accts.max_fill()?;
+    let amount = loop {
+        let amount = accts.bonding_curve.curve.tokens_for_cost(
+            accts.bonding_curve.token_reserves,
+            budget,
+            max_fill,
+        )?;
+        if amount == 0 {
+            break 0;
+        }
+        let total = buy_cost(&accts.global, &accts.bonding_curve, None, false, amount)?.total()?;
+        if total <= sol_in {
+            break amount;
+        }
+        budget = budget.saturating_sub(total - sol_in);
+    };
+    require!(amount >= min_tokens_out, F44Code::TooFewTokensReceived);
+
+    process_buy_sol(accts, ctx.bumps.bonding_curve, amount, sol_in)
+}
+
+fn process_buy_sol(
+    accts: &mut BuySol,
+    bonding_curve_bump: u8,
+    requested_amount: u64,
+    max_sol_amount: u64,
+) -> Result<()> {
+    require!(requested_amount > 0, F44Code::ZeroAmount);
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
+    check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+
+    // only the filled part is charged, so nothing has to be refunded
+    let graduation = accts.graduation();
+    let amount = requested_amount.min(accts.max_fill()?);
+    require!(amount > 0, F44Code::InsufficientLiquidity);
+
+    let BuyCost {
+        f44_cost: sol_cost,
+        creator_fee,
+        f44_transfer_amount: vault_amount,
+        fee_transfer_amount: fee,
+        ..
+    } = buy_cost(&accts.global, &accts.bonding_curve, None, false, amount)?;
+
+    require!(vault_amount > 0, F44Code::InvalidAmount);
+    let total = vault_amount.checked_add(fee).ok_or(F44Code::MathOverflow)?;
+    require!(total <= max_sol_amount, F44Code::TooMuchF44Required);
+
+    // the fee is kept in the vault too, an unfunded fee recipient can't take sub-rent amounts
+    transfer_lamports(
+        &accts.system_program.to_account_info(),
+        &accts.user.to_account_info(),
+        &accts.sol_vault.to_account_info(),
+        total,
+        &[],
+    )?;
+
+    accts.bonding_curve.f44_reserves = accts
+        .bonding_curve
+        .f44_reserves
+        .checked_add(sol_cost)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.bonding_curve.creator_fees = accts
+        .bonding_curve
+        .creator_fees
+        .checked_add(creator_fee)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.bonding_curve.protocol_fees = accts
+        .bonding_curve
+        .protocol_fees
+        .checked_add(fee)
+        .ok_or(F44Code::MathOverflow)?;
+    check_sol_vault(&mut accts.bonding_curve, accts.sol_vault.lamports())?;
+
+    // send token from agent token vault account to user
+    let binding = accts.mint.key();
+    let vault_seeds = &[BONDING_CURVE, binding.as_ref(), &[bonding_curve_bump]];
+    let signer = &[&vault_seeds[..]];
+
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.associated_bonding_curve.to_account_info().clone(),
+            mint: accts.mint.to_account_info().clone(),
+            to: accts.associated_user.to_account_info().clone(),
+            authority: accts.bonding_curve.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.mint.decimals)?;
+
+    let bonding_curve = &mut accts.bonding_curve;
+    bonding_curve.token_reserves = bonding_curve
+        .token_reserves
+        .checked_add(amount)
+        .ok_or(F44Code::MathOverflow)?;
+    bonding_curve.update_price()?;
+
+    msg!("Current market cap is {}", bonding_curve.current_mcap);
+
+    // the built-in pool only pairs with F44, a completed SOL curve is wound down by `withdraw`
+    accts.associated_bonding_curve.reload()?;
+    if completes(&accts.bonding_curve, accts.associated_bonding_curve.amount, graduation)? {
+        accts.bonding_curve.complete = true;
+
+        msg!(
+            "CompleteEvent - user: {}, mint: {}, bondingCurve: {}, timestamp: {}",
+            accts.user.key(),
+            accts.mint.key(),
+            accts.bonding_curve.key(),
+            accts.clock.unix_timestamp
+        );
+
+        emit!(CompleteEvent {
+            user: accts.user.key(),
+            mint: accts.mint.key(),
+            bonding_curve: accts.bonding_curve.key(),
+            timestamp: accts.clock.unix_timestamp,
+        });
+    }
+
+    msg!(
+        "TradeEvent - type: Buy, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, solAmount: {}, tokenAmount: {}, requestedTokenAmount: {}, fee: {}, creatorFee: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        accts.clock.unix_timestamp,
+        vault_amount,
+        amount,
+        requested_amount,
+        fee,
+        creator_fee,
+        true,
+        accts.bonding_curve.token_reserves,
+        accts.bonding_curve.current_price,
+        accts.bonding_curve.current_mcap,
+    );
+
+    emit!(TradeEvent {
+        mint: accts.mint.key(),
+        quote_mint: accts.bonding_curve.quote_mint,
+        amount: vault_amount,
+        token_amount: amount,
+        requested_token_amount: requested_amount,
+        fee,
+        creator_fee,
+        referrer: None,
+        referral_fee: 0,
+        is_buy: true,
+        user: accts.user.key(),
+        timestamp: accts.clock.unix_timestamp,
+        token_reserves: accts.bonding_curve.token_reserves,
+        last_price: accts.bonding_curve.current_price,
+        current_mcap: accts.bonding_curve.current_mcap,
+    });
+
+    Ok(())
+}
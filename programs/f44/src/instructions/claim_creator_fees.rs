@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED, QUOTE_CONFIG_SEED, SOL_VAULT_SEED},
+    error::*,
+    events::*,
+    state::{BondingCurve, Global, QuoteConfig},
+    utils::{
+        check_quote_config, check_quote_vault, check_sol_vault, quote_supply, transfer_lamports,
+    },
+};
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        has_one = creator @ F44Code::NotAuthorized
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    // the curve's quote mint, F44 or a whitelisted mint
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == bonding_curve.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, f44_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_config: Option<Box<Account<'info, QuoteConfig>>>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = f44_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_creator_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFeesSol<'info> {
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        has_one = creator @ F44Code::NotAuthorized,
+        constraint = bonding_curve.is_sol_quoted() @ F44Code::InvalidQuoteMint
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let amount = accts.bonding_curve.creator_fees;
+    require!(amount > 0, F44Code::NoCreatorFees);
+    check_quote_config(
+        &accts.global,
+        &accts.bonding_curve,
+        accts.quote_config.as_deref().map(|c| &**c),
+    )?;
+
+    let global_seeds = &[GLOBAL_STATE_SEED, &[ctx.bumps.global]];
+    let signer = &[&global_seeds[..]];
+    let cpi_ctx = CpiContext::new(
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.f44_vault.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
+            to: accts.associated_creator_f44_account.to_account_info().clone(),
+            authority: accts.global.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.f44_mint.decimals)?;
+
+    accts.bonding_curve.creator_fees = 0;
+    let supply = quote_supply(&mut accts.global, accts.quote_config.as_deref_mut().map(|c| &mut **c));
+    *supply = supply.checked_sub(amount).ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_quote_vault(
+        &mut accts.global,
+        accts.quote_config.as_deref_mut().map(|c| &mut **c),
+        accts.f44_vault.amount,
+    )?;
+
+    msg!(
+        "CreatorFeeClaimed - creator: {}, mint: {}, bondingCurve: {}, amount: {}, timestamp: {}",
+        accts.creator.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        amount,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(CreatorFeeClaimed {
+        creator: accts.creator.key(),
+        mint: accts.mint.key(),
+        bonding_curve: accts.bonding_curve.key(),
+        amount,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn claim_creator_fees_sol(ctx: Context<ClaimCreatorFeesSol>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let amount = accts.bonding_curve.creator_fees;
+    require!(amount > 0, F44Code::NoCreatorFees);
+
+    let mint_key = accts.mint.key();
+    let sol_vault_seeds = &[SOL_VAULT_SEED, mint_key.as_ref(), &[ctx.bumps.sol_vault]];
+    transfer_lamports(
+        &accts.system_program.to_account_info(),
+        &accts.sol_vault.to_account_info(),
+        &accts.creator.to_account_info(),
+        amount,
+        &[&sol_vault_seeds[..]],
+    )?;
+
+    accts.bonding_curve.creator_fees = 0;
+    check_sol_vault(&mut accts.bonding_curve, accts.sol_vault.lamports())?;
+
+    msg!(
+        "CreatorFeeClaimed - creator: {}, mint: {}, bondingCurve: {}, amount: {}, timestamp: {}",
+        accts.creator.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        amount,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(CreatorFeeClaimed {
+        creator: accts.creator.key(),
+        mint: accts.mint.key(),
+        bonding_curve: accts.bonding_curve.key(),
+        amount,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{BONDING_CURVE, GLOBAL_STATE_SEED, SOL_VAULT_SEED},
+    error::*,
+    events::*,
+    state::{BondingCurve, Global},
+    utils::{check_sol_vault, transfer_lamports},
+};
+
+// Sweeps the trading fees a SOL curve kept in its SOL vault to the fee recipient wallet.
+// Anyone may call it. The wallet has to end up rent-exempt, so an unfunded one can only
+// take the fees once they add up to the rent.
+#[derive(Accounts)]
+pub struct ClaimProtocolFeesSol<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_sol_quoted() @ F44Code::InvalidQuoteMint
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// CHECK: the fee recipient wallet itself, lamports need no token account
+    #[account(
+        mut,
+        address = global.fee_recipient @ F44Code::UnValidFeeRecipient
+    )]
+    pub fee_recipient: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn claim_protocol_fees_sol(ctx: Context<ClaimProtocolFeesSol>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let amount = accts.bonding_curve.protocol_fees;
+    require!(amount > 0, F44Code::NoProtocolFees);
+
+    let mint_key = accts.mint.key();
+    let sol_vault_seeds = &[SOL_VAULT_SEED, mint_key.as_ref(), &[ctx.bumps.sol_vault]];
+    transfer_lamports(
+        &accts.system_program.to_account_info(),
+        &accts.sol_vault.to_account_info(),
+        &accts.fee_recipient.to_account_info(),
+        amount,
+        &[&sol_vault_seeds[..]],
+    )?;
+
+    accts.bonding_curve.protocol_fees = 0;
+    check_sol_vault(&mut accts.bonding_curve, accts.sol_vault.lamports())?;
+
+    msg!(
+        "ProtocolFeeClaimed - feeRecipient: {}, mint: {}, bondingCurve: {}, amount: {}, timestamp: {}",
+        accts.fee_recipient.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        amount,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(ProtocolFeeClaimed {
+        fee_recipient: accts.fee_recipient.key(),
+        mint: accts.mint.key(),
+        bonding_curve: accts.bonding_curve.key(),
+        amount,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
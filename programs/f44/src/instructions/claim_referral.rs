@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{F44_VAULT_SEED, GLOBAL_STATE_SEED, REFERRAL_SEED},
+    error::*,
+    events::*,
+    state::{Global, Referral},
+    utils::check_f44_vault,
+};
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [REFERRAL_SEED, referrer.key().as_ref()],
+        bump
+    )]
+    pub referral: Box<Account<'info, Referral>>,
+
+    // referral rewards are only ever accrued in F44
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == global.f44_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = referrer,
+        associated_token::mint = f44_mint,
+        associated_token::authority = referrer,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_referrer_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let amount = accts.referral.unclaimed;
+    require!(amount > 0, F44Code::NoReferralRewards);
+
+    let global_seeds = &[GLOBAL_STATE_SEED, &[ctx.bumps.global]];
+    let signer = &[&global_seeds[..]];
+    let cpi_ctx = CpiContext::new(
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.f44_vault.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
+            to: accts.associated_referrer_f44_account.to_account_info().clone(),
+            authority: accts.global.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), amount, accts.f44_mint.decimals)?;
+
+    accts.referral.unclaimed = 0;
+    accts.global.f44_supply = accts
+        .global
+        .f44_supply
+        .checked_sub(amount)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    msg!(
+        "ReferralClaimed - referrer: {}, amount: {}, timestamp: {}",
+        accts.referrer.key(),
+        amount,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(ReferralClaimed {
+        referrer: accts.referrer.key(),
+        amount,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
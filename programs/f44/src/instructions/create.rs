@@ -1,25 +1,57 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::msg;
+use anchor_lang::solana_program::{msg, program::{invoke, invoke_signed}};
+use anchor_lang::system_program::{create_account, CreateAccount};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, transfer, Mint, Token, TokenAccount, Transfer}
+    token::spl_token::native_mint,
+    metadata::{create_metadata_accounts_v3, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3, Metadata},
+    token_2022::{
+        spl_token_2022::{
+            extension::{metadata_pointer, ExtensionType},
+            instruction::AuthorityType,
+            state::{Account as AccountState, Mint as MintState},
+        },
+        Token2022,
+    },
+    token_interface::{
+        initialize_account3, initialize_mint2, mint_to, set_authority, transfer_checked, InitializeAccount3,
+        InitializeMint2, Mint, MintTo, SetAuthority, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
+use spl_pod::optional_keys::OptionalNonZeroPubkey;
+use spl_token_metadata_interface::{instruction as token_metadata_instruction, state::TokenMetadata};
 use std::mem::size_of;
 use crate::{
-    constants::{GLOBAL_STATE_SEED, F44_VAULT_SEED, BONDING_CURVE, VAULT_SEED},
-    state::{Global, BondingCurve},
+    constants::{GLOBAL_STATE_SEED, F44_VAULT_SEED, BONDING_CURVE, VAULT_SEED, SOL_VAULT_SEED, QUOTE_CONFIG_SEED, MINT_SEED, AGENT_TOKEN_DECIMALS, AGENT_TOKEN_SUPPLY, MAX_CREATOR_FEE_BPS, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, MIN_SOL_GRADUATION_THRESHOLD},
+    state::{Global, BondingCurve, CurveKind, GraduationMetric, QuoteConfig},
     error::*,
     events::*,
+    utils::{amount_with_fee, check_f44_vault, check_not_paused, check_quote_vault, transfer_lamports},
 };
 #[derive(Accounts)]
+#[instruction(curve: CurveKind, mint_nonce: u64)]
 pub struct Create<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
-    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    /// CHECK: a fresh agent mint per launch, created in the handler under `token_program`.
+    /// The global PDA mints the supply and then gives up the authority.
+    #[account(
+        mut,
+        seeds = [MINT_SEED, user.key().as_ref(), &mint_nonce.to_le_bytes()],
+        bump,
+    )]
+    pub mint: UncheckedAccount<'info>,
 
     #[account(
-        init_if_needed,
+        init,
         payer = user,
         seeds = [BONDING_CURVE, mint.key().as_ref()],
         bump,
@@ -27,45 +59,70 @@ pub struct Create<'info> {
     )]
     pub bonding_curve: Box<Account<'info, BondingCurve>>,
 
+    /// CHECK: created in the handler once the mint exists
     #[account(
-        init_if_needed,
-        payer = user,
+        mut,
         seeds = [VAULT_SEED, mint.key().as_ref()],
         bump,
-        token::mint = mint,
-        token::authority = bonding_curve,
     )]
-    pub associated_bonding_curve: Box<Account<'info, TokenAccount>>,
+    pub associated_bonding_curve: UncheckedAccount<'info>,
 
+    /// CHECK: Metaplex metadata, only used for SPL Token mints. Created and validated by the token metadata program
     #[account(mut)]
-    pub associated_user_account: Box<Account<'info, TokenAccount>>,
+    pub metadata: Option<UncheckedAccount<'info>>,
 
-    pub f44_mint: Box<Account<'info, Mint>>,
+    // mint the create fee is paid in: the quote mint for whitelisted quote mints, F44 otherwise
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == match quote_config {
+            Some(_) => quote_mint.key(),
+            None => global.f44_mint,
+        } @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
         seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
         bump,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut)]
-    pub associated_user_f44_account: Box<Account<'info, TokenAccount>>,
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: the asset the curve trades against: the F44 mint, the native mint for SOL
+    /// or a mint with a `quote_config`, checked in the handler
+    pub quote_mint: UncheckedAccount<'info>,
 
+    // settings of a whitelisted quote mint, only needed for curves quoted in one
     #[account(
         mut,
-        seeds = [GLOBAL_STATE_SEED],
-        bump
+        seeds = [QUOTE_CONFIG_SEED, quote_mint.key().as_ref()],
+        bump,
     )]
-    pub global: Box<Account<'info, Global>>,
-   
+    pub quote_config: Option<Box<Account<'info, QuoteConfig>>>,
+
+    // lamport reserves of a SOL curve, only needed when `quote_mint` is the native mint
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    // token program of the agent mint, SPL Token or Token-2022
+    pub token_program: Interface<'info, TokenInterface>,
+    // token program of the F44 mint
+    pub f44_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(
-        mut, 
+        mut,
         seeds = [GLOBAL_STATE_SEED],
         bump,
     )]
@@ -74,108 +131,467 @@ pub struct Deposit<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    pub f44_mint: Box<Account<'info, Mint>>,
+    // deposits are credited to the F44 protocol fees
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == global.f44_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
         bump,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut)]
-    pub associated_owner_account: Box<Account<'info, TokenAccount>>,
+    pub associated_owner_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
 }
 
-pub fn create(ctx: Context<Create>, initial_price: f64, curve_slope: f64, amount: u64) -> Result<()> {
+// curve prices are F44 base units per agent token base unit, scaled by PRICE_SCALE
+// mint_nonce lets one creator launch several tokens; it only feeds the mint PDA seeds
+// SOL curves price in lamports, so unless they graduate on tokens sold they have to pass a lamport threshold
+// SPL Token agent mints get Metaplex metadata, Token-2022 agent mints carry their metadata in the mint
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    ctx: Context<Create>,
+    curve: CurveKind,
+    mint_nonce: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+    creator_fee_bps: u64,
+    graduation_threshold: Option<u64>,
+) -> Result<()> {
     let accts = ctx.accounts;
-    let decimals = accts.mint.decimals;
     let f44_decimals = accts.f44_mint.decimals;
+    let amount = AGENT_TOKEN_SUPPLY * 10_u64.pow(AGENT_TOKEN_DECIMALS.into());
+
+    require!(accts.global.initialized, F44Code::NotInitialized);
+    check_not_paused(&accts.global, None)?;
+    require!(
+        !name.is_empty() && name.len() <= MAX_NAME_LENGTH,
+        F44Code::InvalidMetadata
+    );
+    require!(
+        !symbol.is_empty() && symbol.len() <= MAX_SYMBOL_LENGTH,
+        F44Code::InvalidMetadata
+    );
+    require!(uri.len() <= MAX_URI_LENGTH, F44Code::InvalidMetadata);
+    curve.validate(amount)?;
+    require!(creator_fee_bps <= MAX_CREATOR_FEE_BPS, F44Code::InvalidFeeBps);
 
-    require!(accts.global.initialized == true, F44Code::NotInitialized);
-    require!(amount == accts.global.agent_amount * 10_u64.pow(decimals.into()), F44Code::NotEnoughAmount);
+    // whitelisted quote mints bring their own create fee and thresholds
+    let quote_mint = accts.quote_mint.key();
+    let (create_fee, default_threshold, min_threshold, max_threshold) = match &accts.quote_config {
+        Some(quote_config) => {
+            require!(quote_config.enabled, F44Code::InvalidQuoteMint);
+            (
+                quote_config.create_fee,
+                quote_config.graduation_threshold,
+                quote_config.min_graduation_threshold,
+                quote_config.max_graduation_threshold,
+            )
+        }
+        None => {
+            require!(
+                quote_mint == accts.global.f44_mint || quote_mint == native_mint::ID,
+                F44Code::InvalidQuoteMint
+            );
+            (
+                accts
+                    .global
+                    .fee_amount
+                    .checked_mul(
+                        10_u64
+                            .checked_pow(f44_decimals.into())
+                            .ok_or(F44Code::MathOverflow)?,
+                    )
+                    .ok_or(F44Code::MathOverflow)?,
+                accts.global.graduation_threshold,
+                accts.global.min_graduation_threshold,
+                accts.global.max_graduation_threshold,
+            )
+        }
+    };
+    // creators may only override the default threshold within the admin bounds
+    let graduation_threshold = match graduation_threshold {
+        // the defaults and bounds above are F44 amounts, SOL curves name their own lamport threshold
+        Some(threshold)
+            if quote_mint == native_mint::ID
+                && accts.global.graduation_metric != GraduationMetric::TokensSold =>
+        {
+            require!(
+                threshold >= MIN_SOL_GRADUATION_THRESHOLD,
+                F44Code::InvalidGraduationThreshold
+            );
+            threshold
+        }
+        Some(threshold) => {
+            require!(
+                threshold >= min_threshold && threshold <= max_threshold,
+                F44Code::InvalidGraduationThreshold
+            );
+            threshold
+        }
+        None => {
+            require!(
+                quote_mint != native_mint::ID
+                    || accts.global.graduation_metric == GraduationMetric::TokensSold,
+                F44Code::InvalidGraduationThreshold
+            );
+            default_threshold
+        }
+    };
+
+    let user_key = accts.user.key();
+    let mint_key = accts.mint.key();
+    let nonce_bytes = mint_nonce.to_le_bytes();
+    let mint_bump = [ctx.bumps.mint];
+    let mint_seeds: &[&[u8]] = &[MINT_SEED, user_key.as_ref(), &nonce_bytes, &mint_bump];
+    let global_bump = [ctx.bumps.global];
+    let global_seeds: &[&[u8]] = &[GLOBAL_STATE_SEED, &global_bump];
+    let signer = &[global_seeds];
+
+    if accts.token_program.key() == Token2022::id() {
+        create_token_2022_mint(accts, mint_seeds, global_seeds, &name, &symbol, &uri)?;
+    } else {
+        create_spl_mint(accts, mint_seeds, global_seeds, &name, &symbol, &uri)?;
+    }
+
+    // the vault only holds agent tokens, none of our mint extensions need account extensions
+    let vault_bump = [ctx.bumps.associated_bonding_curve];
+    let vault_seeds: &[&[u8]] = &[VAULT_SEED, mint_key.as_ref(), &vault_bump];
+    let vault_space = ExtensionType::try_calculate_account_len::<AccountState>(&[])?;
+    create_program_account(accts, accts.associated_bonding_curve.to_account_info(), vault_space, 0, vault_seeds)?;
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        InitializeAccount3 {
+            account: accts.associated_bonding_curve.to_account_info(),
+            mint: accts.mint.to_account_info(),
+            authority: accts.bonding_curve.to_account_info(),
+        },
+    );
+    initialize_account3(cpi_ctx)?;
 
+    // mint the whole supply into the bonding curve vault
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
-            from: accts.associated_user_account.to_account_info().clone(),
+        MintTo {
+            mint: accts.mint.to_account_info().clone(),
             to: accts.associated_bonding_curve.to_account_info().clone(),
-            authority: accts.user.to_account_info().clone(),
+            authority: accts.global.to_account_info().clone(),
         },
     );
-    transfer(cpi_ctx, amount)?;
+    mint_to(cpi_ctx.with_signer(signer), amount)?;
+
+    // revoke the mint authority so the supply is fixed forever; no freeze authority is ever set
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        SetAuthority {
+            current_authority: accts.global.to_account_info().clone(),
+            account_or_mint: accts.mint.to_account_info().clone(),
+        },
+    );
+    set_authority(cpi_ctx.with_signer(signer), AuthorityType::MintTokens, None)?;
 
     msg!(
-        "transfer agent tokens from user to bonding curve - Mint: {}, amount: {}, User: {}, Bonding Curve: {}",
+        "mint agent tokens to bonding curve - Mint: {}, amount: {}, User: {}, Bonding Curve: {}",
         accts.mint.key(),
         amount,
         accts.user.key(),
         accts.bonding_curve.key()
     );
-    
+
+    // the vault is credited the full create fee, transfer-fee mints are grossed up
+    let fee_transfer_amount = amount_with_fee(&accts.f44_mint.to_account_info(), create_fee)?;
+    let vault_before = accts.f44_vault.amount;
     let cpi_fee_ctx = CpiContext::new(
-        accts.token_program.to_account_info(),
-        Transfer {
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
             from: accts.associated_user_f44_account.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
             to: accts.f44_vault.to_account_info().clone(),
             authority: accts.user.to_account_info().clone(),
         },
     );
-    transfer(cpi_fee_ctx, accts.global.fee_amount * 10_u64.pow(f44_decimals.into()))?;
+    transfer_checked(cpi_fee_ctx, fee_transfer_amount, f44_decimals)?;
+    accts.f44_vault.reload()?;
+    let fee_received = accts.f44_vault.amount - vault_before;
+    let protocol_fees = match accts.quote_config.as_deref_mut() {
+        Some(quote_config) => &mut quote_config.protocol_fees,
+        None => &mut accts.global.protocol_fees,
+    };
+    *protocol_fees = protocol_fees
+        .checked_add(fee_received)
+        .ok_or(F44Code::MathOverflow)?;
+    check_quote_vault(
+        &mut accts.global,
+        accts.quote_config.as_deref_mut().map(|c| &mut **c),
+        accts.f44_vault.amount,
+    )?;
     msg!(
         "transfer f44 tokens from user to bonding reserve pool as fee - F44: {}, amount: {}",
         accts.f44_mint.key(),
-        accts.global.fee_amount * 10_u64.pow(f44_decimals.into()),
+        fee_transfer_amount,
     );
+    // a SOL vault is funded with its rent up front so the reserves can be paid out in full
+    if quote_mint == native_mint::ID {
+        let sol_vault = accts.sol_vault.as_ref().ok_or(F44Code::InvalidQuoteMint)?;
+        let rent = Rent::get()?.minimum_balance(0);
+        let top_up = rent.saturating_sub(sol_vault.lamports());
+        if top_up > 0 {
+            transfer_lamports(
+                &accts.system_program.to_account_info(),
+                &accts.user.to_account_info(),
+                &sol_vault.to_account_info(),
+                top_up,
+                &[],
+            )?;
+        }
+    }
+
     // init the bonding curve
-    accts.bonding_curve.initial_price = initial_price;
-    accts.bonding_curve.current_price = initial_price;
-    accts.bonding_curve.current_mcap = 0.0;
-    accts.bonding_curve.curve_slope = curve_slope;
-    accts.bonding_curve.token_reserves = 0.0;
-    accts.bonding_curve.token_total_supply = (amount / 10_u64.pow(decimals.into())) as f64;
-    accts.bonding_curve.mcap_limit = 100000.0;
+    accts.bonding_curve.curve = curve;
+    accts.bonding_curve.token_reserves = 0;
+    accts.bonding_curve.token_total_supply = amount;
+    accts.bonding_curve.f44_reserves = 0;
+    accts.bonding_curve.graduation_metric = accts.global.graduation_metric;
+    accts.bonding_curve.graduation_threshold = graduation_threshold;
+    accts.bonding_curve.update_price()?;
     accts.bonding_curve.complete = false;
+    accts.bonding_curve.migrated = false;
     accts.bonding_curve.token_mint = accts.mint.key();
+    accts.bonding_curve.quote_mint = quote_mint;
+    accts.bonding_curve.creator = accts.user.key();
+    accts.bonding_curve.creator_fee_bps = creator_fee_bps;
+    accts.bonding_curve.creator_fees = 0;
+    accts.bonding_curve.protocol_fees = 0;
+    accts.bonding_curve.paused = false;
+    accts.bonding_curve.sell_only = false;
 
     // Log the event details
     msg!(
-        "CreateEvent - Mint: {}, bondingCurve: {}, user: {}, initialPrice: {}",
+        "CreateEvent - Mint: {}, bondingCurve: {}, user: {}, curve: {:?}, creatorFeeBps: {}, quoteMint: {}, graduationMetric: {:?}, graduationThreshold: {}, name: {}, symbol: {}, uri: {}",
         accts.mint.key(),
         accts.bonding_curve.key(),
         accts.user.key(),
-        accts.bonding_curve.initial_price,
+        accts.bonding_curve.curve,
+        creator_fee_bps,
+        accts.bonding_curve.quote_mint,
+        accts.bonding_curve.graduation_metric,
+        graduation_threshold,
+        name,
+        symbol,
+        uri,
     );
-    
+
     emit!{
         CreateEvent {
             mint: accts.mint.key(),
             bonding_curve: accts.bonding_curve.key(),
             user: accts.user.key(),
-            initial_price: accts.bonding_curve.initial_price,
+            curve: accts.bonding_curve.curve,
+            creator_fee_bps,
+            quote_mint: accts.bonding_curve.quote_mint,
+            graduation_metric: accts.bonding_curve.graduation_metric,
+            graduation_threshold,
+            name,
+            symbol,
+            uri,
         }
     }
 
     Ok(())
 }
 
+// Allocates a PDA owned by the agent token program. `extra_rent_space` is funded but not allocated,
+// for data the token program appends later.
+fn create_program_account<'info>(
+    accts: &Create<'info>,
+    account: AccountInfo<'info>,
+    space: usize,
+    extra_rent_space: usize,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(space + extra_rent_space);
+    let cpi_ctx = CpiContext::new(
+        accts.system_program.to_account_info(),
+        CreateAccount {
+            from: accts.user.to_account_info(),
+            to: account,
+        },
+    );
+    create_account(
+        cpi_ctx.with_signer(&[seeds]),
+        lamports,
+        space as u64,
+        &accts.token_program.key(),
+    )
+}
+
+fn initialize_agent_mint(accts: &Create) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        InitializeMint2 {
+            mint: accts.mint.to_account_info(),
+        },
+    );
+    initialize_mint2(cpi_ctx, AGENT_TOKEN_DECIMALS, &accts.global.key(), None)
+}
+
+fn create_spl_mint(
+    accts: &Create,
+    mint_seeds: &[&[u8]],
+    global_seeds: &[&[u8]],
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Result<()> {
+    let (Some(metadata), Some(token_metadata_program)) =
+        (&accts.metadata, &accts.token_metadata_program)
+    else {
+        return err!(F44Code::MetadataAccountsRequired);
+    };
+
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&[])?;
+    create_program_account(accts, accts.mint.to_account_info(), space, 0, mint_seeds)?;
+    initialize_agent_mint(accts)?;
+
+    // the global PDA stays update authority but the metadata is immutable
+    let cpi_ctx = CpiContext::new(
+        token_metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: metadata.to_account_info(),
+            mint: accts.mint.to_account_info(),
+            mint_authority: accts.global.to_account_info(),
+            payer: accts.user.to_account_info(),
+            update_authority: accts.global.to_account_info(),
+            system_program: accts.system_program.to_account_info(),
+            rent: accts.rent.to_account_info(),
+        },
+    );
+    create_metadata_accounts_v3(
+        cpi_ctx.with_signer(&[global_seeds]),
+        DataV2 {
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+            seller_fee_basis_points: 0,
+            creators: None,
+            collection: None,
+            uses: None,
+        },
+        false,
+        true,
+        None,
+    )
+}
+
+fn create_token_2022_mint(
+    accts: &Create,
+    mint_seeds: &[&[u8]],
+    global_seeds: &[&[u8]],
+    name: &str,
+    symbol: &str,
+    uri: &str,
+) -> Result<()> {
+    let mint_key = accts.mint.key();
+    let global_key = accts.global.key();
+    let token_metadata = TokenMetadata {
+        update_authority: OptionalNonZeroPubkey::try_from(Some(global_key))?,
+        mint: mint_key,
+        name: name.to_string(),
+        symbol: symbol.to_string(),
+        uri: uri.to_string(),
+        additional_metadata: vec![],
+    };
+
+    // the metadata extension is appended by the token program, only its rent is paid up front
+    let space = ExtensionType::try_calculate_account_len::<MintState>(&[ExtensionType::MetadataPointer])?;
+    create_program_account(
+        accts,
+        accts.mint.to_account_info(),
+        space,
+        token_metadata.tlv_size_of()?,
+        mint_seeds,
+    )?;
+
+    // the mint points at itself for metadata and nobody can repoint it
+    let account_infos = [
+        accts.mint.to_account_info(),
+        accts.global.to_account_info(),
+        accts.token_program.to_account_info(),
+    ];
+    invoke(
+        &metadata_pointer::instruction::initialize(
+            &accts.token_program.key(),
+            &mint_key,
+            None,
+            Some(mint_key),
+        )?,
+        &account_infos,
+    )?;
+    initialize_agent_mint(accts)?;
+
+    invoke_signed(
+        &token_metadata_instruction::initialize(
+            &accts.token_program.key(),
+            &mint_key,
+            &global_key,
+            &mint_key,
+            &global_key,
+            token_metadata.name,
+            token_metadata.symbol,
+            token_metadata.uri,
+        ),
+        &account_infos,
+        &[global_seeds],
+    )?;
+
+    // renounce the update authority so the metadata is as immutable as the Metaplex path
+    invoke_signed(
+        &token_metadata_instruction::update_authority(
+            &accts.token_program.key(),
+            &mint_key,
+            &global_key,
+            OptionalNonZeroPubkey::default(),
+        ),
+        &account_infos,
+        &[global_seeds],
+    )?;
+
+    Ok(())
+}
+
 pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     let accts = ctx.accounts;
+    let vault_before = accts.f44_vault.amount;
     // Deposit f44 to the vault account
     let cpi_ctx = CpiContext::new(
-        accts.token_program.to_account_info(),
-        Transfer {
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
             from: accts.associated_owner_account.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
             to: accts.f44_vault.to_account_info().clone(),
             authority: accts.owner.to_account_info().clone(),
         },
     );
-    transfer(cpi_ctx, amount)?;
+    transfer_checked(cpi_ctx, amount, accts.f44_mint.decimals)?;
 
-    accts.global.f44_supply += amount;
+    // only what actually arrived is credited, transfer fees are withheld on the way in
+    accts.f44_vault.reload()?;
+    let received = accts.f44_vault.amount - vault_before;
+    accts.global.protocol_fees = accts
+        .global
+        .protocol_fees
+        .checked_add(received)
+        .ok_or(F44Code::MathOverflow)?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
 
     Ok(())
-}
\ No newline at end of file
+}
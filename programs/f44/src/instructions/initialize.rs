@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint,Token,TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    constants::{GLOBAL_STATE_SEED, F44_VAULT_SEED},
-    state::Global,
+    constants::{GLOBAL_STATE_SEED, F44_VAULT_SEED, DEFAULT_MCAP_LIMIT},
+    state::{Global, GraduationMetric},
     error::*,
 };
 use std::mem::size_of;
@@ -22,7 +22,10 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    pub f44_mint: Box<Account<'info, Mint>>,
+    #[account(
+        mint::token_program = f44_token_program,
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         init,
@@ -31,22 +34,27 @@ pub struct Initialize<'info> {
         bump,
         token::mint = f44_mint,
         token::authority = global,
+        token::token_program = f44_token_program,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
     let accts = ctx.accounts;
 
-    require!(accts.global.initialized == false, F44Code::AlreadyInitialized);
+    require!(!accts.global.initialized, F44Code::AlreadyInitialized);
     
     accts.global.authority = accts.owner.key();
     accts.global.initialized = true;
     accts.global.f44_mint = accts.f44_mint.key();
     accts.global.f44_vault = accts.f44_vault.key();
+    accts.global.graduation_metric = GraduationMetric::MarketCap;
+    accts.global.graduation_threshold = DEFAULT_MCAP_LIMIT * 10_u64.pow(accts.f44_mint.decimals.into());
+    accts.global.min_graduation_threshold = 1;
+    accts.global.max_graduation_threshold = u64::MAX;
     
     Ok(())
 }
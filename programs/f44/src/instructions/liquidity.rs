@@ -0,0 +1,354 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+use std::mem::size_of;
+
+use crate::{
+    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED, POOL_SEED, POSITION_SEED},
+    error::*,
+    events::*,
+    math::Rounding,
+    state::{BondingCurve, Global, LiquidityPosition, Pool},
+    utils::{amount_with_fee, check_can_sell, check_f44_vault, check_not_paused, transfer_fee},
+};
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [POSITION_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump,
+        space = 8 + size_of::<LiquidityPosition>()
+    )]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve,
+        token::token_program = token_program,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == pool.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = f44_mint,
+        associated_token::authority = user,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        seeds = [POSITION_SEED, pool.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Box<Account<'info, LiquidityPosition>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve,
+        token::token_program = token_program,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == pool.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = f44_mint,
+        associated_token::authority = user,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Mints `shares` of the pool against a proportional deposit of both sides, rounded up.
+pub fn add_liquidity(
+    ctx: Context<AddLiquidity>,
+    shares: u64,
+    max_f44_amount: u64,
+    max_token_amount: u64,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+
+    let (f44_amount, token_amount) = accts.pool.amounts_for_shares(shares, Rounding::Up)?;
+    let f44_transfer_amount = amount_with_fee(&accts.f44_mint.to_account_info(), f44_amount)?;
+    require!(
+        f44_transfer_amount <= max_f44_amount && token_amount <= max_token_amount,
+        F44Code::TooMuchLiquidityRequired
+    );
+
+    let vault_before = accts.f44_vault.amount;
+    let cpi_ctx = CpiContext::new(
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.associated_user_f44_account.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
+            to: accts.f44_vault.to_account_info().clone(),
+            authority: accts.user.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx, f44_transfer_amount, accts.f44_mint.decimals)?;
+    accts.f44_vault.reload()?;
+    require!(
+        accts.f44_vault.amount - vault_before >= f44_amount,
+        F44Code::InsufficientF44Received
+    );
+
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.associated_user.to_account_info().clone(),
+            mint: accts.mint.to_account_info().clone(),
+            to: accts.associated_bonding_curve.to_account_info().clone(),
+            authority: accts.user.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx, token_amount, accts.mint.decimals)?;
+
+    accts.pool.add_liquidity(shares, f44_amount, token_amount)?;
+    accts.global.f44_supply = accts
+        .global
+        .f44_supply
+        .checked_add(f44_amount)
+        .ok_or(F44Code::MathOverflow)?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    let position = &mut accts.position;
+    position.pool = accts.pool.key();
+    position.owner = accts.user.key();
+    position.shares = position
+        .shares
+        .checked_add(shares)
+        .ok_or(F44Code::MathOverflow)?;
+
+    msg!(
+        "LiquidityEvent - type: Add, user: {}, mint: {}, pool: {}, shares: {}, f44Amount: {}, tokenAmount: {}, lpSupply: {}, timestamp: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.pool.key(),
+        shares,
+        f44_amount,
+        token_amount,
+        accts.pool.lp_supply,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(LiquidityEvent {
+        user: accts.user.key(),
+        mint: accts.mint.key(),
+        pool: accts.pool.key(),
+        is_add: true,
+        shares,
+        f44_amount,
+        token_amount,
+        lp_supply: accts.pool.lp_supply,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Burns `shares` for a proportional part of both reserves, rounded down.
+pub fn remove_liquidity(
+    ctx: Context<RemoveLiquidity>,
+    shares: u64,
+    min_f44_amount: u64,
+    min_token_amount: u64,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    // an exit like a sell, so a sell-only pause lets it through
+    check_can_sell(&accts.global, &accts.bonding_curve)?;
+
+    require!(
+        accts.position.shares >= shares,
+        F44Code::InsufficientShares
+    );
+
+    let (f44_amount, token_amount) = accts.pool.amounts_for_shares(shares, Rounding::Down)?;
+    let f44_received = f44_amount - transfer_fee(&accts.f44_mint.to_account_info(), f44_amount)?;
+    require!(
+        f44_received >= min_f44_amount && token_amount >= min_token_amount,
+        F44Code::TooLittleLiquidityReceived
+    );
+
+    let global_seeds = &[GLOBAL_STATE_SEED, &[ctx.bumps.global]];
+    let signer = &[&global_seeds[..]];
+    let cpi_ctx = CpiContext::new(
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.f44_vault.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
+            to: accts.associated_user_f44_account.to_account_info().clone(),
+            authority: accts.global.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), f44_amount, accts.f44_mint.decimals)?;
+
+    let binding = accts.mint.key();
+    let curve_seeds = &[BONDING_CURVE, binding.as_ref(), &[ctx.bumps.bonding_curve]];
+    let signer = &[&curve_seeds[..]];
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        TransferChecked {
+            from: accts.associated_bonding_curve.to_account_info().clone(),
+            mint: accts.mint.to_account_info().clone(),
+            to: accts.associated_user.to_account_info().clone(),
+            authority: accts.bonding_curve.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), token_amount, accts.mint.decimals)?;
+
+    accts.pool.remove_liquidity(shares, f44_amount, token_amount)?;
+    accts.position.shares -= shares;
+    accts.global.f44_supply = accts
+        .global
+        .f44_supply
+        .checked_sub(f44_amount)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    msg!(
+        "LiquidityEvent - type: Remove, user: {}, mint: {}, pool: {}, shares: {}, f44Amount: {}, tokenAmount: {}, lpSupply: {}, timestamp: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.pool.key(),
+        shares,
+        f44_amount,
+        token_amount,
+        accts.pool.lp_supply,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(LiquidityEvent {
+        user: accts.user.key(),
+        mint: accts.mint.key(),
+        pool: accts.pool.key(),
+        is_add: false,
+        shares,
+        f44_amount,
+        token_amount,
+        lp_supply: accts.pool.lp_supply,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
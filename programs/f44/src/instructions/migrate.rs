@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{self, Token},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    amm::{self, InitializePool},
+    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED},
+    error::*,
+    events::*,
+    state::{BondingCurve, Global},
+    utils::{check_f44_vault, check_not_paused},
+};
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.quote_mint == global.f44_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve,
+        token::token_program = token_program,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == global.f44_mint @ F44Code::InvalidTokenAccount
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // the curve's F44 proceeds are staged here so the bonding curve can deposit both sides
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = f44_mint,
+        associated_token::authority = bonding_curve,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_bonding_curve_f44: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub lp_mint: UncheckedAccount<'info>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub pool_f44_vault: UncheckedAccount<'info>,
+
+    /// CHECK: created and validated by the AMM
+    #[account(mut)]
+    pub pool_token_vault: UncheckedAccount<'info>,
+
+    /// CHECK: the bonding curve's LP token account, created by the AMM and read back in the handler
+    #[account(mut)]
+    pub associated_bonding_curve_lp: UncheckedAccount<'info>,
+
+    /// CHECK: checked against `global.amm_program`
+    #[account(
+        executable,
+        constraint = amm_program.key() == global.amm_program @ F44Code::InvalidAmmProgram
+    )]
+    pub amm_program: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub lp_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Moves a completed curve's liquidity into an AMM pool and burns the LP tokens, so the
+// pool can never be drained. Anyone can crank this once the curve completes.
+pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(accts.bonding_curve.complete, F44Code::BondingCurveNotComplete);
+    check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+    require!(!accts.bonding_curve.migrated, F44Code::AlreadyMigrated);
+
+    let f44_raised = accts.bonding_curve.f44_reserves;
+    let token_amount = accts.associated_bonding_curve.amount;
+    require!(f44_raised > 0 && token_amount > 0, F44Code::InsufficientLiquidity);
+
+    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+    let global_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let signer = &[&global_seeds[..]];
+
+    let cpi_ctx = CpiContext::new(
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
+            from: accts.f44_vault.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
+            to: accts.associated_bonding_curve_f44.to_account_info().clone(),
+            authority: accts.global.to_account_info().clone(),
+        },
+    );
+    transfer_checked(cpi_ctx.with_signer(signer), f44_raised, accts.f44_mint.decimals)?;
+    accts.bonding_curve.f44_reserves = 0;
+    accts.global.f44_supply = accts
+        .global
+        .f44_supply
+        .checked_sub(f44_raised)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    // a transfer-fee F44 mint may have withheld part of it
+    accts.associated_bonding_curve_f44.reload()?;
+    let f44_amount = accts.associated_bonding_curve_f44.amount;
+
+    let binding = accts.mint.key();
+    let (_, bump) = Pubkey::find_program_address(&[BONDING_CURVE, binding.as_ref()], ctx.program_id);
+    let curve_seeds = &[BONDING_CURVE, binding.as_ref(), &[bump]];
+    let signer = &[&curve_seeds[..]];
+
+    amm::initialize_pool(
+        &accts.amm_program.to_account_info(),
+        InitializePool {
+            payer: accts.user.to_account_info(),
+            creator: accts.bonding_curve.to_account_info(),
+            mint_a: accts.f44_mint.to_account_info(),
+            mint_b: accts.mint.to_account_info(),
+            pool: accts.pool.to_account_info(),
+            lp_mint: accts.lp_mint.to_account_info(),
+            vault_a: accts.pool_f44_vault.to_account_info(),
+            vault_b: accts.pool_token_vault.to_account_info(),
+            creator_token_a: accts.associated_bonding_curve_f44.to_account_info(),
+            creator_token_b: accts.associated_bonding_curve.to_account_info(),
+            creator_lp: accts.associated_bonding_curve_lp.to_account_info(),
+            token_program_a: accts.f44_token_program.to_account_info(),
+            token_program_b: accts.token_program.to_account_info(),
+            lp_token_program: accts.lp_token_program.to_account_info(),
+            associated_token_program: accts.associated_token_program.to_account_info(),
+            system_program: accts.system_program.to_account_info(),
+        },
+        f44_amount,
+        token_amount,
+        signer,
+    )?;
+
+    // burn every LP token the curve received so the liquidity is locked for good
+    let lp_burned = {
+        let lp_info = accts.associated_bonding_curve_lp.to_account_info();
+        require!(
+            *lp_info.owner == accts.lp_token_program.key(),
+            F44Code::InvalidTokenAccount
+        );
+        let lp_account = token::TokenAccount::try_deserialize(&mut &lp_info.try_borrow_data()?[..])?;
+        require!(
+            lp_account.owner == accts.bonding_curve.key() && lp_account.mint == accts.lp_mint.key(),
+            F44Code::InvalidTokenAccount
+        );
+        lp_account.amount
+    };
+    let cpi_ctx = CpiContext::new(
+        accts.lp_token_program.to_account_info(),
+        token::Burn {
+            mint: accts.lp_mint.to_account_info(),
+            from: accts.associated_bonding_curve_lp.to_account_info(),
+            authority: accts.bonding_curve.to_account_info(),
+        },
+    );
+    token::burn(cpi_ctx.with_signer(signer), lp_burned)?;
+
+    accts.bonding_curve.migrated = true;
+
+    msg!(
+        "MigrateEvent - user: {}, mint: {}, bondingCurve: {}, pool: {}, f44Amount: {}, tokenAmount: {}, lpBurned: {}, timestamp: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        accts.pool.key(),
+        f44_amount,
+        token_amount,
+        lp_burned,
+        accts.clock.unix_timestamp
+    );
+
+    emit!(MigrateEvent {
+        user: accts.user.key(),
+        mint: accts.mint.key(),
+        bonding_curve: accts.bonding_curve.key(),
+        pool: accts.pool.key(),
+        f44_amount,
+        token_amount,
+        lp_burned,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -4,6 +4,23 @@ pub mod buy;
 pub mod set_params;
 pub mod sell;
 pub mod withdraw;
+pub mod upgrade_bonding_curve;
+pub mod upgrade_global;
+pub mod migrate;
+pub mod swap;
+pub mod liquidity;
+pub mod claim_creator_fees;
+pub mod claim_protocol_fees;
+pub mod claim_referral;
+pub mod quote;
+pub mod set_oracle;
+pub mod push_price;
+pub mod buy_sol;
+pub mod sell_sol;
+pub mod quote_config;
+pub mod authority;
+pub mod admin;
+pub mod pause;
 
 pub use initialize::*;
 pub use create::*;
@@ -11,3 +28,20 @@ pub use buy::*;
 pub use set_params::*;
 pub use sell::*;
 pub use withdraw::*;
+pub use upgrade_bonding_curve::*;
+pub use upgrade_global::*;
+pub use migrate::*;
+pub use swap::*;
+pub use liquidity::*;
+pub use claim_creator_fees::*;
+pub use claim_protocol_fees::*;
+pub use claim_referral::*;
+pub use quote::*;
+pub use set_oracle::*;
+pub use push_price::*;
+pub use buy_sol::*;
+pub use sell_sol::*;
+pub use quote_config::*;
+pub use authority::*;
+pub use admin::*;
+pub use pause::*;
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{
+        ADMIN_ACTION_SEED, ADMIN_SET_SEED, BONDING_CURVE, GLOBAL_STATE_SEED,
+        MAX_PAUSE_REASON_LENGTH,
+    },
+    error::*,
+    events::*,
+    instructions::check_authority,
+    state::{AdminAction, AdminActionKind, AdminSet, BondingCurve, Global},
+};
+
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = authority @ F44Code::NotAuthorized
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub authority: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct SetCurvePause<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    pub authority: Signer<'info>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// A pause stops buy, create, withdraw and, on the built-in pool, swap buys and deposits. With
+// sell_only set, holders can still sell and take out liquidity, so a pause never locks anyone's
+// funds in. sell_only means nothing unpaused.
+pub fn set_global_pause(
+    ctx: Context<SetGlobalPause>,
+    paused: bool,
+    sell_only: bool,
+    reason: String,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    pause_global(&mut accts.global, paused, sell_only, reason, accts.clock.unix_timestamp)
+}
+
+pub fn set_curve_pause(
+    ctx: Context<SetCurvePause>,
+    paused: bool,
+    sell_only: bool,
+    reason: String,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    require!(reason.len() <= MAX_PAUSE_REASON_LENGTH, F44Code::InvalidPauseReason);
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::SetCurvePause {
+            mint: accts.mint.key(),
+            paused,
+            sell_only,
+            reason: reason.clone(),
+        },
+    )?;
+
+    accts.bonding_curve.paused = paused;
+    accts.bonding_curve.sell_only = paused && sell_only;
+
+    emit_pause(
+        Some(accts.bonding_curve.key()),
+        paused,
+        accts.bonding_curve.sell_only,
+        reason,
+        accts.clock.unix_timestamp,
+    );
+
+    Ok(())
+}
+
+// shared with the admin multisig, callers check who may act
+pub(crate) fn pause_global(
+    global: &mut Global,
+    paused: bool,
+    sell_only: bool,
+    reason: String,
+    timestamp: i64,
+) -> Result<()> {
+    require!(reason.len() <= MAX_PAUSE_REASON_LENGTH, F44Code::InvalidPauseReason);
+
+    global.paused = paused;
+    global.sell_only = paused && sell_only;
+
+    emit_pause(None, paused, global.sell_only, reason, timestamp);
+
+    Ok(())
+}
+
+fn emit_pause(
+    bonding_curve: Option<Pubkey>,
+    paused: bool,
+    sell_only: bool,
+    reason: String,
+    timestamp: i64,
+) {
+    msg!(
+        "PauseEvent - bondingCurve: {:?}, paused: {}, sellOnly: {}, reason: {}, timestamp: {}",
+        bonding_curve,
+        paused,
+        sell_only,
+        reason,
+        timestamp,
+    );
+
+    emit!(PauseEvent {
+        bonding_curve,
+        paused,
+        sell_only,
+        reason,
+        timestamp,
+    });
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+use crate::{
+    constants::{ADMIN_ACTION_SEED, ADMIN_SET_SEED, GLOBAL_STATE_SEED, PRICE_FEED_SEED},
+    error::*,
+    instructions::check_authority,
+    state::{AdminAction, AdminActionKind, AdminSet, Global, PriceFeed},
+};
+
+#[derive(Accounts)]
+pub struct PushPrice<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [PRICE_FEED_SEED],
+        bump,
+        space = 8 + size_of::<PriceFeed>()
+    )]
+    pub price_feed: Box<Account<'info, PriceFeed>>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+
+    pub system_program: Program<'info, System>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Publishes the USD price of one whole F44 as price * 10^expo, stamped with the current time.
+pub fn push_price(ctx: Context<PushPrice>, price: i64, conf: u64, expo: i32) -> Result<()> {
+    let accts = ctx.accounts;
+    require!(price > 0, F44Code::InvalidPrice);
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::PushPrice { price, conf, expo },
+    )?;
+
+    let price_feed = &mut accts.price_feed;
+    price_feed.price = price;
+    price_feed.conf = conf;
+    price_feed.expo = expo;
+    price_feed.publish_time = accts.clock.unix_timestamp;
+
+    msg!(
+        "Pushed F44 price - price: {}, conf: {}, expo: {}, publishTime: {}",
+        price,
+        conf,
+        expo,
+        price_feed.publish_time,
+    );
+
+    Ok(())
+}
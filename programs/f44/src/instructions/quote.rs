@@ -0,0 +1,210 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+
+use crate::{
+    constants::{BONDING_CURVE, BPS_DENOMINATOR, GLOBAL_STATE_SEED},
+    error::*,
+    instructions::{buy_cost, completes, graduation, max_fill, sell_proceeds},
+    math::{market_cap, mul_div, to_u64, Rounding},
+    state::{BondingCurve, Global, GraduationMetric},
+};
+
+// Read-only views over a bonding curve. Nothing is written, the result is returned
+// through Anchor return data so clients can simulate a transaction for a quote.
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        token::mint = mint,
+        token::authority = bonding_curve,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // the curve's quote mint, any mint for SOL curves as lamports carry no transfer fee
+    #[account(
+        constraint = bonding_curve.is_sol_quoted()
+            || f44_mint.key() == bonding_curve.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: checked against `global.price_oracle` and parsed by `OraclePrice::load`
+    #[account(
+        constraint = price_oracle.key() == global.price_oracle @ F44Code::InvalidOracle
+    )]
+    pub price_oracle: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct BuyQuote {
+    // the amount that would be filled
+    pub token_amount: u64,
+    // F44 owed to the curve, before fees
+    pub f44_cost: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    // everything the buyer pays, transfer fees included
+    pub total_f44: u64,
+    // how far the trade moves the price, in basis points of the current price
+    pub price_impact_bps: u64,
+    pub price_after: u128,
+    pub mcap_after: u64,
+    pub completes: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SellQuote {
+    pub token_amount: u64,
+    // F44 paid out by the curve, before fees
+    pub f44_proceeds: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    // what reaches the seller, transfer fees excluded
+    pub f44_received: u64,
+    pub price_impact_bps: u64,
+    pub price_after: u128,
+    pub mcap_after: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CurveStateQuote {
+    // unit of every F44 amount in the quotes, lamports for SOL curves
+    pub quote_mint: Pubkey,
+    pub token_reserves: u64,
+    pub token_total_supply: u64,
+    pub f44_reserves: u64,
+    pub current_price: u128,
+    pub current_mcap: u64,
+    pub graduation_metric: GraduationMetric,
+    pub graduation_threshold: u64,
+    pub complete: bool,
+    pub migrated: bool,
+}
+
+pub fn quote_buy(ctx: Context<Quote>, amount: u64) -> Result<BuyQuote> {
+    let accts = ctx.accounts;
+    let bonding_curve = &accts.bonding_curve;
+
+    require!(!bonding_curve.complete, F44Code::BondingCurveComplete);
+
+    // filled the way `buy` would fill it: up to the threshold and what the vault holds
+    let graduation = graduation(
+        &accts.global,
+        bonding_curve,
+        accts.price_oracle.as_ref().map(|o| o.to_account_info()).as_ref(),
+        accts.f44_mint.decimals,
+        Clock::get()?.unix_timestamp,
+    )?;
+    let amount = amount.min(max_fill(
+        bonding_curve,
+        accts.associated_bonding_curve.amount,
+        graduation,
+    )?);
+    require!(amount > 0, F44Code::InsufficientLiquidity);
+    let f44_mint_info = accts.f44_mint.to_account_info();
+    let quote_mint_info = (!bonding_curve.is_sol_quoted()).then_some(f44_mint_info);
+    let cost = buy_cost(
+        &accts.global,
+        bonding_curve,
+        quote_mint_info.as_ref(),
+        false,
+        amount,
+    )?;
+    let mut after = (**bonding_curve).clone();
+    after.token_reserves = after
+        .token_reserves
+        .checked_add(amount)
+        .ok_or(F44Code::InvalidReserves)?;
+    after.f44_reserves = after
+        .f44_reserves
+        .checked_add(cost.f44_cost)
+        .ok_or(F44Code::MathOverflow)?;
+    after.update_price()?;
+
+    Ok(BuyQuote {
+        token_amount: amount,
+        f44_cost: cost.f44_cost,
+        fee: cost.fee,
+        creator_fee: cost.creator_fee,
+        total_f44: cost.total()?,
+        price_impact_bps: price_impact_bps(bonding_curve.current_price, after.current_price)?,
+        price_after: after.current_price,
+        mcap_after: after.current_mcap,
+        completes: completes(
+            &after,
+            accts.associated_bonding_curve.amount - amount,
+            graduation,
+        )?,
+    })
+}
+
+pub fn quote_sell(ctx: Context<Quote>, amount: u64) -> Result<SellQuote> {
+    let accts = ctx.accounts;
+    let bonding_curve = &accts.bonding_curve;
+
+    require!(!bonding_curve.complete, F44Code::BondingCurveComplete);
+
+    let f44_mint_info = accts.f44_mint.to_account_info();
+    let quote_mint_info = (!bonding_curve.is_sol_quoted()).then_some(f44_mint_info);
+    let proceeds = sell_proceeds(
+        &accts.global,
+        bonding_curve,
+        quote_mint_info.as_ref(),
+        false,
+        amount,
+    )?;
+    let price_after = bonding_curve
+        .curve
+        .price_at(bonding_curve.token_reserves - amount)?;
+
+    Ok(SellQuote {
+        token_amount: amount,
+        f44_proceeds: proceeds.f44_amount,
+        fee: proceeds.fee,
+        creator_fee: proceeds.creator_fee,
+        f44_received: proceeds.f44_received,
+        price_impact_bps: price_impact_bps(bonding_curve.current_price, price_after)?,
+        price_after,
+        mcap_after: market_cap(price_after, bonding_curve.token_total_supply)?,
+    })
+}
+
+pub fn quote_curve_state(ctx: Context<Quote>) -> Result<CurveStateQuote> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+
+    Ok(CurveStateQuote {
+        quote_mint: bonding_curve.quote_mint,
+        token_reserves: bonding_curve.token_reserves,
+        token_total_supply: bonding_curve.token_total_supply,
+        f44_reserves: bonding_curve.f44_reserves,
+        current_price: bonding_curve.current_price,
+        current_mcap: bonding_curve.current_mcap,
+        graduation_metric: bonding_curve.graduation_metric,
+        graduation_threshold: bonding_curve.graduation_threshold,
+        complete: bonding_curve.complete,
+        migrated: bonding_curve.migrated,
+    })
+}
+
+// Relative move from `before` to `after` in basis points, rounded up.
+fn price_impact_bps(before: u128, after: u128) -> Result<u64> {
+    require!(before > 0, F44Code::InvalidPrice);
+    to_u64(mul_div(
+        before.abs_diff(after),
+        BPS_DENOMINATOR as u128,
+        before,
+        Rounding::Up,
+    )?)
+}
@@ -0,0 +1,327 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use std::mem::size_of;
+
+use crate::{
+    constants::{
+        ADMIN_ACTION_SEED, ADMIN_SET_SEED, F44_VAULT_SEED, GLOBAL_STATE_SEED, QUOTE_CONFIG_SEED,
+    },
+    error::*,
+    instructions::{check_authority, params_effective_at},
+    state::{AdminAction, AdminActionKind, AdminSet, Global, QuoteConfig, QuoteParams},
+};
+
+#[derive(Accounts)]
+pub struct AddQuoteMint<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    // F44 and SOL are quoted without a config
+    #[account(
+        mint::token_program = quote_token_program,
+        constraint = quote_mint.key() != global.f44_mint
+            && quote_mint.key() != native_mint::ID @ F44Code::InvalidQuoteMint
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [QUOTE_CONFIG_SEED, quote_mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<QuoteConfig>()
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [F44_VAULT_SEED, quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = global,
+        token::token_program = quote_token_program,
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+
+    pub system_program: Program<'info, System>,
+    pub quote_token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateQuoteMint<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, quote_config.quote_mint.as_ref()],
+        bump
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    pub authority: Signer<'info>,
+    pub clock: Sysvar<'info, Clock>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelQuoteMintUpdate<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, quote_config.quote_mint.as_ref()],
+        bump
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    pub authority: Signer<'info>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyQuoteMintUpdate<'info> {
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, quote_config.quote_mint.as_ref()],
+        bump
+    )]
+    pub quote_config: Box<Account<'info, QuoteConfig>>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// amounts are in base units of the quote mint
+pub fn add_quote_mint(
+    ctx: Context<AddQuoteMint>,
+    create_fee: u64,
+    graduation_threshold: u64,
+    min_graduation_threshold: u64,
+    max_graduation_threshold: u64,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::AddQuoteMint {
+            quote_mint: accts.quote_mint.key(),
+            create_fee,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
+        },
+    )?;
+
+    // no curve is quoted in the mint yet, so its first settings need no timelock
+    accts.quote_config.quote_mint = accts.quote_mint.key();
+    accts.quote_config.vault = accts.quote_vault.key();
+    accts.quote_config.supply = 0;
+    accts.quote_config.protocol_fees = 0;
+    accts.quote_config.pending_params_at = 0;
+    store_config(
+        &mut accts.quote_config,
+        &QuoteParams {
+            enabled: true,
+            create_fee,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
+        },
+    )
+}
+
+// Queues the change behind the global `params_delay`, `apply_quote_mint_update` applies it.
+// A new change replaces the queued one. Disabling a mint only stops new launches against it,
+// existing curves keep trading, so that part takes effect right away.
+pub fn update_quote_mint(
+    ctx: Context<UpdateQuoteMint>,
+    enabled: bool,
+    create_fee: u64,
+    graduation_threshold: u64,
+    min_graduation_threshold: u64,
+    max_graduation_threshold: u64,
+) -> Result<()> {
+    let accts = ctx.accounts;
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::UpdateQuoteMint {
+            quote_mint: accts.quote_config.quote_mint,
+            enabled,
+            create_fee,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
+        },
+    )?;
+
+    let params = QuoteParams {
+        enabled,
+        create_fee,
+        graduation_threshold,
+        min_graduation_threshold,
+        max_graduation_threshold,
+    };
+    validate_config(&params)?;
+
+    let quote_config = &mut accts.quote_config;
+    let effective_at = params_effective_at(&accts.global, accts.clock.unix_timestamp)?;
+    quote_config.pending_params = params;
+    quote_config.pending_params_at = effective_at;
+    if !enabled {
+        quote_config.enabled = false;
+    }
+
+    msg!(
+        "Quote mint update queued - quote mint: {}, effective at: {}, params: {:?}",
+        quote_config.quote_mint,
+        effective_at,
+        quote_config.pending_params,
+    );
+
+    Ok(())
+}
+
+// anyone may apply a queued change once its delay is over
+pub fn apply_quote_mint_update(ctx: Context<ApplyQuoteMintUpdate>) -> Result<()> {
+    let quote_config = &mut ctx.accounts.quote_config;
+
+    require!(quote_config.pending_params_at != 0, F44Code::NoPendingParams);
+    require!(
+        ctx.accounts.clock.unix_timestamp >= quote_config.pending_params_at,
+        F44Code::ParamsTimelocked
+    );
+
+    let params = quote_config.pending_params;
+    quote_config.pending_params_at = 0;
+    store_config(quote_config, &params)
+}
+
+// Drops the queued change. A disable that already took effect stays, re-enabling is
+// queued like any other update.
+pub fn cancel_quote_mint_update(ctx: Context<CancelQuoteMintUpdate>) -> Result<()> {
+    let accts = ctx.accounts;
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::CancelQuoteMintUpdate {
+            quote_mint: accts.quote_config.quote_mint,
+        },
+    )?;
+
+    let quote_config = &mut accts.quote_config;
+    require!(quote_config.pending_params_at != 0, F44Code::NoPendingParams);
+
+    msg!(
+        "Quote mint update cancelled - quote mint: {}, was effective at: {}, params: {:?}",
+        quote_config.quote_mint,
+        quote_config.pending_params_at,
+        quote_config.pending_params,
+    );
+    quote_config.pending_params_at = 0;
+
+    Ok(())
+}
+
+fn validate_config(params: &QuoteParams) -> Result<()> {
+    require!(
+        params.min_graduation_threshold > 0
+            && params.min_graduation_threshold <= params.graduation_threshold
+            && params.graduation_threshold <= params.max_graduation_threshold,
+        F44Code::InvalidGraduationThreshold
+    );
+    Ok(())
+}
+
+fn store_config(quote_config: &mut QuoteConfig, params: &QuoteParams) -> Result<()> {
+    validate_config(params)?;
+
+    quote_config.enabled = params.enabled;
+    quote_config.create_fee = params.create_fee;
+    quote_config.graduation_threshold = params.graduation_threshold;
+    quote_config.min_graduation_threshold = params.min_graduation_threshold;
+    quote_config.max_graduation_threshold = params.max_graduation_threshold;
+
+    msg!("Set quote mint\n
+        quote mint is {:?}\n
+        enabled is {:?}\n
+        create fee is {:?}\n
+        graduation threshold is {:?}\n
+        min graduation threshold is {:?}\n
+        max graduation threshold is {:?}",
+        quote_config.quote_mint,
+        quote_config.enabled,
+        quote_config.create_fee,
+        quote_config.graduation_threshold,
+        quote_config.min_graduation_threshold,
+        quote_config.max_graduation_threshold,
+    );
+
+    Ok(())
+}
@@ -1,14 +1,25 @@
 use anchor_lang::prelude::*;
+use std::mem::size_of;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer},
+    token_interface::{
+        burn, transfer_checked, Burn, Mint, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
 use crate::{
-    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED},
+    constants::{
+        BONDING_CURVE, BPS_DENOMINATOR, F44_VAULT_SEED, GLOBAL_STATE_SEED, QUOTE_CONFIG_SEED,
+        REFERRAL_SEED,
+    },
     error::*,
     events::*,
-    state::{BondingCurve, Global},
+    math::{bps_fee, mul_div, to_u64, Rounding},
+    state::{BondingCurve, Global, QuoteConfig, Referral},
+    utils::{
+        amount_with_fee, check_can_sell, check_quote_config, check_quote_vault, credit_referral, quote_supply,
+        referral_share, transfer_fee,
+    },
 };
 
 #[derive(Accounts)]
@@ -20,8 +31,11 @@ pub struct Sell<'info> {
     )]
     pub global: Box<Account<'info, Global>>,
 
-    #[account(mut)]
-    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -34,91 +48,278 @@ pub struct Sell<'info> {
         mut,
         token::mint = mint,
         token::authority = bonding_curve,
+        token::token_program = token_program,
     )]
-    pub associated_bonding_curve: Box<Account<'info, TokenAccount>>,
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
     )]
-    pub associated_user: Box<Account<'info, TokenAccount>>,
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub f44_mint: Box<Account<'info, Mint>>,
+    // the curve's quote mint, F44 or a whitelisted mint
+    #[account(
+        mut,
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == bonding_curve.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
         bump,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // vault accounting of a whitelisted quote mint, only for curves quoted in one
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, f44_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_config: Option<Box<Account<'info, QuoteConfig>>>,
 
     #[account(
         init_if_needed,
         payer = user,
         associated_token::mint = f44_mint,
-        associated_token::authority = user
+        associated_token::authority = user,
+        associated_token::token_program = f44_token_program,
     )]
-    pub associated_user_f44_account: Box<Account<'info, TokenAccount>>,
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = f44_mint,
+        token::token_program = f44_token_program,
+        constraint = fee_recipient.owner == global.fee_recipient @ F44Code::UnValidFeeRecipient
+    )]
+    pub fee_recipient: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: any wallet that routed the trade, only used as the seed of its referral account
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [REFERRAL_SEED, referrer.as_ref().map(|r| r.key()).unwrap_or_default().as_ref()],
+        bump,
+        space = 8 + size_of::<Referral>()
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
 
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub clock: Sysvar<'info, Clock>,
 }
 
+// What selling `amount` agent tokens pays out, fees included.
+pub(crate) struct SellProceeds {
+    pub f44_amount: u64,
+    pub fee: u64,
+    pub creator_fee: u64,
+    pub referral_fee: u64,
+    pub user_amount: u64,
+    // what reaches the user after any transfer fee
+    pub f44_received: u64,
+}
+
+pub(crate) fn sell_proceeds(
+    global: &Global,
+    bonding_curve: &BondingCurve,
+    // None for SOL curves, lamports have no transfer fee
+    quote_mint_info: Option<&AccountInfo>,
+    referred: bool,
+    amount: u64,
+) -> Result<SellProceeds> {
+    // Calculate the F44 output for the given token amount, rounded down in favour of the curve
+    let f44_amount = bonding_curve
+        .curve
+        .proceeds_from_sell(bonding_curve.token_reserves, amount)?;
+
+    // A curve can only pay out what was paid into it
+    require!(
+        f44_amount <= bonding_curve.f44_reserves,
+        F44Code::InsufficientLiquidity
+    );
+
+    // the trading and creator fees are taken out of the proceeds
+    let fee = bps_fee(f44_amount, global.trade_fee_bps)?;
+    let creator_fee = bps_fee(f44_amount, bonding_curve.creator_fee_bps)?;
+    // a referrer's share of the trading fee stays in the vault until claimed
+    let referral_fee = if referred { referral_share(global, fee)? } else { 0 };
+    let user_amount = f44_amount
+        .checked_sub(fee)
+        .and_then(|v| v.checked_sub(creator_fee))
+        .ok_or(F44Code::InvalidAmount)?;
+    let f44_received = match quote_mint_info {
+        Some(info) => user_amount - transfer_fee(info, user_amount)?,
+        None => user_amount,
+    };
+
+    Ok(SellProceeds {
+        f44_amount,
+        fee,
+        creator_fee,
+        referral_fee,
+        user_amount,
+        f44_received,
+    })
+}
+
+impl<'info> Sell<'info> {
+    fn proceeds(&self, amount: u64) -> Result<SellProceeds> {
+        sell_proceeds(
+            &self.global,
+            &self.bonding_curve,
+            Some(&self.f44_mint.to_account_info()),
+            self.referral.is_some(),
+            amount,
+        )
+    }
+}
+
 pub fn sell(ctx: Context<Sell>, amount: u64, min_f44_output: u64) -> Result<()> {
+    process_sell(ctx.accounts, ctx.bumps.global, amount, min_f44_output)
+}
+
+// f44_out is the F44 amount the user has to receive, after fees
+// max_tokens_in is the most agent tokens that may be burnt for it
+pub fn sell_exact_out(ctx: Context<Sell>, f44_out: u64, max_tokens_in: u64) -> Result<()> {
     let accts = ctx.accounts;
-    let bonding_curve = &accts.bonding_curve;
-    let decimals = accts.mint.decimals;
-    let f44_decimals = accts.f44_mint.decimals;
 
+    require!(f44_out > 0, F44Code::ZeroAmount);
     require!(
-        accts.bonding_curve.complete == false,
+        !accts.bonding_curve.complete,
         F44Code::BondingCurveComplete
     );
-    require!(amount > 0, F44Code::ZeroAmount);
+
+    // Start from the output grossed up for fees, then raise the target until the user nets f44_out
+    let fee_bps = accts
+        .global
+        .trade_fee_bps
+        .checked_add(accts.bonding_curve.creator_fee_bps)
+        .filter(|bps| *bps < BPS_DENOMINATOR)
+        .ok_or(F44Code::InvalidFeeBps)?;
+    let f44_gross = amount_with_fee(&accts.f44_mint.to_account_info(), f44_out)?;
+    let mut target = to_u64(mul_div(
+        f44_gross as u128,
+        BPS_DENOMINATOR as u128,
+        (BPS_DENOMINATOR - fee_bps) as u128,
+        Rounding::Up,
+    )?)?;
+    let amount = loop {
+        let amount = accts
+            .bonding_curve
+            .curve
+            .tokens_for_proceeds(accts.bonding_curve.token_reserves, target)?;
+        let f44_received = accts.proceeds(amount)?.f44_received;
+        if f44_received >= f44_out {
+            break amount;
+        }
+        target = target
+            .checked_add(f44_out - f44_received)
+            .ok_or(F44Code::MathOverflow)?;
+    };
+    require!(amount <= max_tokens_in, F44Code::TooManyTokensRequired);
+
+    process_sell(accts, ctx.bumps.global, amount, f44_out)
+}
+
+fn process_sell(accts: &mut Sell, global_bump: u8, amount: u64, min_f44_output: u64) -> Result<()> {
+    require!(
+        !accts.bonding_curve.complete,
+        F44Code::BondingCurveComplete
+    );
+    check_can_sell(&accts.global, &accts.bonding_curve)?;
+    check_quote_config(
+        &accts.global,
+        &accts.bonding_curve,
+        accts.quote_config.as_deref().map(|c| &**c),
+    )?;
+    // referral rewards are only paid in F44
     require!(
-        bonding_curve.token_reserves >= amount as f64 / 10_u64.pow(decimals.into()) as f64,
-        F44Code::NotEnoughAmount
+        accts.quote_config.is_none() || accts.referral.is_none(),
+        F44Code::InvalidReferrer
     );
 
-    // Calculate the required SOL cost for the given token amount
-    let token_amount = amount as f64 / 10_u64.pow(decimals.into()) as f64;
-    let f44_amount = calculate_f44_cost(bonding_curve, token_amount)?;
-    accts.bonding_curve.current_price = bonding_curve.curve_slope
-        * (bonding_curve.token_reserves as f64 - token_amount)
-        + bonding_curve.initial_price;
+    let SellProceeds {
+        f44_amount,
+        fee,
+        creator_fee,
+        referral_fee,
+        user_amount,
+        f44_received,
+    } = accts.proceeds(amount)?;
+    let referrer = accts.referrer.as_ref().map(|r| r.key());
+    credit_referral(
+        accts.user.key(),
+        referrer,
+        accts.referral.as_deref_mut().map(|r| &mut **r),
+        referral_fee,
+    )?;
 
-    // Ensure the SOL cost does not exceed max_sol_cost
+    // Ensure what reaches the user after any transfer fee is not less than min_f44_output
     require!(
-        f44_amount * 10_u64.pow(f44_decimals.into()) as f64 >= min_f44_output as f64,
+        f44_received >= min_f44_output,
         F44Code::TooLittleF44Received
     );
 
     // send f44 token from pool reserve to user
-    let (_, bump) = Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
-    let global_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+    let global_seeds = &[GLOBAL_STATE_SEED, &[global_bump]];
     let signer = &[&global_seeds[..]];
 
     let cpi_ctx = CpiContext::new(
-        accts.token_program.to_account_info(),
-        Transfer {
+        accts.f44_token_program.to_account_info(),
+        TransferChecked {
             from: accts.f44_vault.to_account_info().clone(),
+            mint: accts.f44_mint.to_account_info().clone(),
             to: accts.associated_user_f44_account.to_account_info().clone(),
             authority: accts.global.to_account_info().clone(),
         },
     );
-    transfer(
-        cpi_ctx.with_signer(signer),
-        (f44_amount * 10_u64.pow(f44_decimals.into()) as f64) as u64,
+    transfer_checked(cpi_ctx.with_signer(signer), user_amount, accts.f44_mint.decimals)?;
+
+    let protocol_fee = fee - referral_fee;
+    if protocol_fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            accts.f44_token_program.to_account_info(),
+            TransferChecked {
+                from: accts.f44_vault.to_account_info().clone(),
+                mint: accts.f44_mint.to_account_info().clone(),
+                to: accts.fee_recipient.to_account_info().clone(),
+                authority: accts.global.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), protocol_fee, accts.f44_mint.decimals)?;
+    }
+    // the creator fee stays in the vault, owed to the creator
+    accts.bonding_curve.f44_reserves -= f44_amount;
+    accts.bonding_curve.creator_fees = accts
+        .bonding_curve
+        .creator_fees
+        .checked_add(creator_fee)
+        .ok_or(F44Code::MathOverflow)?;
+    let supply = quote_supply(&mut accts.global, accts.quote_config.as_deref_mut().map(|c| &mut **c));
+    *supply = supply
+        .checked_sub(f44_amount - creator_fee - referral_fee)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_quote_vault(
+        &mut accts.global,
+        accts.quote_config.as_deref_mut().map(|c| &mut **c),
+        accts.f44_vault.amount,
     )?;
-    accts.global.f44_supply -= (f44_amount * 10_u64.pow(f44_decimals.into()) as f64) as u64;
 
-    // send tokens to the vault
+    // burn agent tokens
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
         Burn {
@@ -128,28 +329,26 @@ pub fn sell(ctx: Context<Sell>, amount: u64, min_f44_output: u64) -> Result<()>
         },
     );
     burn(cpi_ctx, amount)?;
-    // burn agent tokens
 
     //  update the bonding curve
-    let decimals = accts.mint.decimals;
-    accts.bonding_curve.token_reserves -= amount as f64 / 10_u64.pow(decimals.into()) as f64;
-
-    // Calculate market cap
-    let macp = (accts.bonding_curve.curve_slope * accts.bonding_curve.token_reserves
-        + accts.bonding_curve.initial_price)
-        * accts.bonding_curve.token_total_supply;
-    accts.bonding_curve.current_mcap = macp;
+    let bonding_curve = &mut accts.bonding_curve;
+    bonding_curve.token_reserves -= amount;
+    bonding_curve.update_price()?;
 
     // Log the TradeEvent details
 
     msg!(
-        "TradeEvent - type: Sell, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, f44Amount: {}, amount: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
+        "TradeEvent - type: Sell, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, f44Amount: {}, amount: {}, fee: {}, creatorFee: {}, referrer: {:?}, referralFee: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
         accts.user.key(),
         accts.mint.key(),
         accts.bonding_curve.key(),
         accts.clock.unix_timestamp,
-        f44_amount,
+        user_amount,
         amount,
+        protocol_fee,
+        creator_fee,
+        referrer,
+        referral_fee,
         false,
         accts.bonding_curve.token_reserves,
         accts.bonding_curve.current_price,
@@ -158,8 +357,14 @@ pub fn sell(ctx: Context<Sell>, amount: u64, min_f44_output: u64) -> Result<()>
 
     emit!(TradeEvent {
         mint: accts.mint.key(),
-        amount: f44_amount as u64,
+        quote_mint: accts.f44_mint.key(),
+        amount: user_amount,
         token_amount: amount,
+        requested_token_amount: amount,
+        fee: protocol_fee,
+        creator_fee,
+        referrer,
+        referral_fee,
         is_buy: false,
         user: accts.user.key(),
         timestamp: accts.clock.unix_timestamp,
@@ -170,23 +375,3 @@ pub fn sell(ctx: Context<Sell>, amount: u64, min_f44_output: u64) -> Result<()>
 
     Ok(())
 }
-
-fn calculate_f44_cost(bonding_curve: &Account<BondingCurve>, token_amount: f64) -> Result<f64> {
-    let first_price = bonding_curve.curve_slope * bonding_curve.token_reserves as f64
-        + bonding_curve.initial_price;
-    let last_price = bonding_curve.curve_slope
-        * (bonding_curve.token_reserves as f64 - token_amount)
-        + bonding_curve.initial_price;
-    msg!(
-        "The first price is {} and last price is {}",
-        first_price.clone(),
-        last_price.clone()
-    );
-    let average_price = (first_price + last_price) / 2.0;
-    msg!("The average price of token is {}", average_price);
-
-    let f44_amount = token_amount * average_price;
-    msg!("The F44 amount is {}", f44_amount);
-
-    Ok(f44_amount)
-}
@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{burn, Burn, Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{BONDING_CURVE, BPS_DENOMINATOR, GLOBAL_STATE_SEED, SOL_VAULT_SEED},
+    error::*,
+    events::*,
+    instructions::{sell_proceeds, SellProceeds},
+    math::{mul_div, to_u64, Rounding},
+    state::{BondingCurve, Global},
+    utils::{check_can_sell, check_sol_vault, transfer_lamports},
+};
+
+// Sell on a SOL curve. The proceeds are paid in lamports out of the curve's SOL vault and
+// the fees stay in it, see `BuySol`.
+#[derive(Accounts)]
+pub struct SellSol<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump,
+        constraint = bonding_curve.is_sol_quoted() @ F44Code::InvalidQuoteMint
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// amount is the agent token amount
+// min_sol_output is the least lamports the user has to receive, after fees
+pub fn sell_sol(ctx: Context<SellSol>, amount: u64, min_sol_output: u64) -> Result<()> {
+    process_sell_sol(ctx.accounts, ctx.bumps.sol_vault, amount, min_sol_output)
+}
+
+// sol_out is the lamports the user has to receive, after fees
+// max_tokens_in is the most agent tokens that may be burnt for it
+pub fn sell_sol_exact_out(ctx: Context<SellSol>, sol_out: u64, max_tokens_in: u64) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(sol_out > 0, F44Code::ZeroAmount);
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
+
+    // Start from the output grossed up for fees, then raise the target until the user nets sol_out
+    let fee_bps = accts
+        .global
+        .trade_fee_bps
+        .checked_add(accts.bonding_curve.creator_fee_bps)
+        .filter(|bps| *bps < BPS_DENOMINATOR)
+        .ok_or(F44Code::InvalidFeeBps)?;
+    let mut target = to_u64(mul_div(
+        sol_out as u128,
+        BPS_DENOMINATOR as u128,
+        (BPS_DENOMINATOR - fee_bps) as u128,
+        Rounding::Up,
+    )?)?;
+    let amount = loop {
+        let amount = accts
+            .bonding_curve
+            .curve
+            .tokens_for_proceeds(accts.bonding_curve.token_reserves, target)?;
+        let user_amount = sell_proceeds(&accts.global, &accts.bonding_curve, None, false, amount)?.user_amount;
+        if user_amount >= sol_out {
+            break amount;
+        }
+        target = target
+            .checked_add(sol_out - user_amount)
+            .ok_or(F44Code::MathOverflow)?;
+    };
+    require!(amount <= max_tokens_in, F44Code::TooManyTokensRequired);
+
+    process_sell_sol(accts, ctx.bumps.sol_vault, amount, sol_out)
+}
+
+fn process_sell_sol(accts: &mut SellSol, sol_vault_bump: u8, amount: u64, min_sol_output: u64) -> Result<()> {
+    require!(!accts.bonding_curve.complete, F44Code::BondingCurveComplete);
+    check_can_sell(&accts.global, &accts.bonding_curve)?;
+
+    let SellProceeds {
+        f44_amount: sol_amount,
+        fee,
+        creator_fee,
+        user_amount,
+        ..
+    } = sell_proceeds(&accts.global, &accts.bonding_curve, None, false, amount)?;
+
+    require!(user_amount >= min_sol_output, F44Code::TooLittleF44Received);
+
+    let mint_key = accts.mint.key();
+    let sol_vault_seeds = &[SOL_VAULT_SEED, mint_key.as_ref(), &[sol_vault_bump]];
+    let signer = &[&sol_vault_seeds[..]];
+    transfer_lamports(
+        &accts.system_program.to_account_info(),
+        &accts.sol_vault.to_account_info(),
+        &accts.user.to_account_info(),
+        user_amount,
+        signer,
+    )?;
+
+    // the creator and trading fees stay in the SOL vault, owed to the creator and the protocol
+    accts.bonding_curve.f44_reserves -= sol_amount;
+    accts.bonding_curve.creator_fees = accts
+        .bonding_curve
+        .creator_fees
+        .checked_add(creator_fee)
+        .ok_or(F44Code::MathOverflow)?;
+    accts.bonding_curve.protocol_fees = accts
+        .bonding_curve
+        .protocol_fees
+        .checked_add(fee)
+        .ok_or(F44Code::MathOverflow)?;
+    check_sol_vault(&mut accts.bonding_curve, accts.sol_vault.lamports())?;
+
+    // burn agent tokens
+    let cpi_ctx = CpiContext::new(
+        accts.token_program.to_account_info(),
+        Burn {
+            mint: accts.mint.to_account_info().clone(),
+            from: accts.associated_user.to_account_info().clone(),
+            authority: accts.user.to_account_info().clone(),
+        },
+    );
+    burn(cpi_ctx, amount)?;
+
+    let bonding_curve = &mut accts.bonding_curve;
+    bonding_curve.token_reserves -= amount;
+    bonding_curve.update_price()?;
+
+    msg!(
+        "TradeEvent - type: Sell, user: {}, mint: {}, bondingCurve: {}, timestamp: {}, solAmount: {}, amount: {}, fee: {}, creatorFee: {}, isBuy: {}, tokenReserves: {}, currentPrice: {}, currentMcap: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        accts.clock.unix_timestamp,
+        user_amount,
+        amount,
+        fee,
+        creator_fee,
+        false,
+        accts.bonding_curve.token_reserves,
+        accts.bonding_curve.current_price,
+        accts.bonding_curve.current_mcap,
+    );
+
+    emit!(TradeEvent {
+        mint: accts.mint.key(),
+        quote_mint: accts.bonding_curve.quote_mint,
+        amount: user_amount,
+        token_amount: amount,
+        requested_token_amount: amount,
+        fee,
+        creator_fee,
+        referrer: None,
+        referral_fee: 0,
+        is_buy: false,
+        user: accts.user.key(),
+        timestamp: accts.clock.unix_timestamp,
+        token_reserves: accts.bonding_curve.token_reserves,
+        last_price: accts.bonding_curve.current_price,
+        current_mcap: accts.bonding_curve.current_mcap,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::{
+    state::{Global, ParamsUpdate},
+    constants::GLOBAL_STATE_SEED,
+    error::*,
+    instructions::queue_params,
+};
+
+#[derive(Accounts)]
+pub struct SetOracle<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+// price_oracle set to the default pubkey turns USD graduation off. Queued behind `params_delay`
+// like `set_params`, so it replaces any queued params change.
+pub fn set_oracle(ctx: Context<SetOracle>, price_oracle: Pubkey, usd_graduation_threshold: u64, oracle_max_age: u64, oracle_max_confidence_bps: u64) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+
+    require!(global.authority == ctx.accounts.user.key(), F44Code::NotAuthorized);
+
+    update_oracle(global, price_oracle, usd_graduation_threshold, oracle_max_age, oracle_max_confidence_bps)
+}
+
+// shared with the admin multisig, callers check who may act
+pub(crate) fn update_oracle(global: &mut Global, price_oracle: Pubkey, usd_graduation_threshold: u64, oracle_max_age: u64, oracle_max_confidence_bps: u64) -> Result<()> {
+    queue_params(global, &ParamsUpdate {
+        price_oracle: Some(price_oracle),
+        usd_graduation_threshold: Some(usd_graduation_threshold),
+        oracle_max_age: Some(oracle_max_age),
+        oracle_max_confidence_bps: Some(oracle_max_confidence_bps),
+        ..Default::default()
+    })
+}
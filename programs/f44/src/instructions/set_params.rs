@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use crate::{
-    state::Global,
-    constants::GLOBAL_STATE_SEED,
+    state::{Global, GraduationMetric, Params, ParamsUpdate},
+    constants::{
+        AGENT_TOKEN_DECIMALS, AGENT_TOKEN_SUPPLY, BPS_DENOMINATOR, GLOBAL_STATE_SEED, MAX_FEE_AMOUNT,
+        MAX_PARAMS_DELAY, MAX_TRADE_FEE_BPS,
+    },
     error::*,
+    events::*,
 };
 
 #[derive(Accounts)]
@@ -18,29 +22,335 @@ pub struct SetParams<'info> {
     pub user: Signer<'info>,
 }
 
-pub fn set_params(ctx: Context<SetParams>, fee_recipient: Pubkey,owner_wallet: Pubkey, agent_amount: u64, fee_amount: u64, create_fee: u64) -> Result<()> {
+#[derive(Accounts)]
+pub struct ApplyPendingParams<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub clock: Sysvar<'info, Clock>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPendingParams<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump,
+        has_one = authority @ F44Code::NotAuthorized
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub authority: Signer<'info>,
+}
+
+// Queues the change, it takes effect through `apply_pending_params` after `params_delay`.
+// None keeps the current value. A new change replaces the queued one and waits the full
+// delay again, so fields it leaves out fall back to the current values, not the queued ones.
+#[allow(clippy::too_many_arguments)]
+pub fn set_params(ctx: Context<SetParams>, fee_recipient: Option<Pubkey>, owner_wallet: Option<Pubkey>, fee_amount: Option<u64>, amm_program: Option<Pubkey>, trade_fee_bps: Option<u64>, referral_fee_bps: Option<u64>, graduation_metric: Option<GraduationMetric>, graduation_threshold: Option<u64>, min_graduation_threshold: Option<u64>, max_graduation_threshold: Option<u64>, params_delay: Option<u64>) -> Result<()> {
     let global = &mut ctx.accounts.global;
 
     require!(global.authority == ctx.accounts.user.key(), F44Code::NotAuthorized);
 
-    global.fee_recipient = fee_recipient;
-    global.owner_wallet = owner_wallet;
-    global.agent_amount = agent_amount;
-    global.fee_amount = fee_amount;
-    global.create_fee = create_fee;
-
-    msg!("Set params\n
-        fee recipient is {:?}\n
-        owner wallet is {:?}\n
-        agent amount is {:?}\n
-        fee basis points is {:?}\n
-        create fee is {:?}", 
+    queue_params(global, &ParamsUpdate {
+        fee_recipient,
+        owner_wallet,
+        fee_amount,
+        amm_program,
+        trade_fee_bps,
+        referral_fee_bps,
+        graduation_metric,
+        graduation_threshold,
+        min_graduation_threshold,
+        max_graduation_threshold,
+        params_delay,
+        ..Default::default()
+    })
+}
+
+// anyone may apply a queued change once its delay is over
+pub fn apply_pending_params(ctx: Context<ApplyPendingParams>) -> Result<()> {
+    let global = &mut ctx.accounts.global;
+    let now = ctx.accounts.clock.unix_timestamp;
+
+    require!(global.pending_params_at != 0, F44Code::NoPendingParams);
+    require!(now >= global.pending_params_at, F44Code::ParamsTimelocked);
+
+    let old = current_params(global);
+    let new = global.pending_params.clone();
+    let effective_at = global.pending_params_at;
+    store_params(global, &new);
+    global.pending_params_at = 0;
+
+    emit!(SetParamsEvent {
+        old,
+        new,
+        effective_at,
+        applied: true,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_pending_params(ctx: Context<CancelPendingParams>) -> Result<()> {
+    clear_pending_params(&mut ctx.accounts.global)
+}
+
+// Merges `update` over the current params, validates the result and queues it behind the
+// current delay. Shared by `set_params` and the admin multisig.
+pub(crate) fn queue_params(global: &mut Global, update: &ParamsUpdate) -> Result<()> {
+    require!(!update.is_empty(), F44Code::InvalidParams);
+    let params = update.apply_to(&current_params(global));
+    validate_params(&params)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let effective_at = params_effective_at(global, now)?;
+    global.pending_params = params;
+    global.pending_params_at = effective_at;
+
+    msg!(
+        "Params queued - effective at: {}, params: {:?}",
+        effective_at,
+        global.pending_params,
+    );
+
+    emit!(SetParamsEvent {
+        old: current_params(global),
+        new: global.pending_params.clone(),
+        effective_at,
+        applied: false,
+        timestamp: now,
+    });
+
+    Ok(())
+}
+
+// When a change queued now may be applied. The delay in force when queueing applies,
+// so shortening it is timelocked too.
+pub(crate) fn params_effective_at(global: &Global, now: i64) -> Result<i64> {
+    Ok(now
+        .checked_add(global.params_delay as i64)
+        .ok_or(F44Code::MathOverflow)?
+        // 0 marks an empty queue
+        .max(1))
+}
+
+fn validate_params(params: &Params) -> Result<()> {
+    require!(params.fee_recipient != Pubkey::default(), F44Code::UnValidFeeRecipient);
+    require!(params.owner_wallet != Pubkey::default(), F44Code::InvalidParams);
+    require!(params.fee_amount <= MAX_FEE_AMOUNT, F44Code::InvalidParams);
+    // the default amm program selects the built-in pool, only the program itself is no AMM
+    require!(params.amm_program != crate::ID, F44Code::InvalidAmmProgram);
+
+    require!(params.trade_fee_bps <= MAX_TRADE_FEE_BPS, F44Code::InvalidFeeBps);
+    require!(params.referral_fee_bps <= BPS_DENOMINATOR, F44Code::InvalidFeeBps);
+    // referrers are paid out of the trading fee
+    require!(
+        params.referral_fee_bps == 0 || params.trade_fee_bps > 0,
+        F44Code::InvalidFeeBps
+    );
+
+    require!(
+        params.min_graduation_threshold > 0
+            && params.min_graduation_threshold <= params.graduation_threshold
+            && params.graduation_threshold <= params.max_graduation_threshold,
+        F44Code::InvalidGraduationThreshold
+    );
+    // a curve can never sell more than the whole supply
+    if params.graduation_metric == GraduationMetric::TokensSold {
+        let supply = AGENT_TOKEN_SUPPLY
+            .checked_mul(10_u64.pow(AGENT_TOKEN_DECIMALS.into()))
+            .ok_or(F44Code::MathOverflow)?;
+        require!(
+            params.max_graduation_threshold <= supply,
+            F44Code::InvalidGraduationThreshold
+        );
+    }
+
+    require!(params.params_delay <= MAX_PARAMS_DELAY, F44Code::InvalidParamsDelay);
+    require!(
+        params.oracle_max_confidence_bps <= BPS_DENOMINATOR,
+        F44Code::InvalidFeeBps
+    );
+    Ok(())
+}
+
+pub(crate) fn clear_pending_params(global: &mut Global) -> Result<()> {
+    require!(global.pending_params_at != 0, F44Code::NoPendingParams);
+
+    msg!(
+        "Params change cancelled - was effective at: {}, params: {:?}",
+        global.pending_params_at,
+        global.pending_params,
+    );
+    global.pending_params_at = 0;
+
+    Ok(())
+}
+
+fn current_params(global: &Global) -> Params {
+    Params {
+        fee_recipient: global.fee_recipient,
+        owner_wallet: global.owner_wallet,
+        agent_amount: global.agent_amount,
+        fee_amount: global.fee_amount,
+        create_fee: global.create_fee,
+        amm_program: global.amm_program,
+        trade_fee_bps: global.trade_fee_bps,
+        referral_fee_bps: global.referral_fee_bps,
+        graduation_metric: global.graduation_metric,
+        graduation_threshold: global.graduation_threshold,
+        min_graduation_threshold: global.min_graduation_threshold,
+        max_graduation_threshold: global.max_graduation_threshold,
+        params_delay: global.params_delay,
+        price_oracle: global.price_oracle,
+        usd_graduation_threshold: global.usd_graduation_threshold,
+        oracle_max_age: global.oracle_max_age,
+        oracle_max_confidence_bps: global.oracle_max_confidence_bps,
+    }
+}
+
+fn store_params(global: &mut Global, params: &Params) {
+    global.fee_recipient = params.fee_recipient;
+    global.owner_wallet = params.owner_wallet;
+    global.fee_amount = params.fee_amount;
+    global.amm_program = params.amm_program;
+    global.trade_fee_bps = params.trade_fee_bps;
+    global.referral_fee_bps = params.referral_fee_bps;
+    global.graduation_metric = params.graduation_metric;
+    global.graduation_threshold = params.graduation_threshold;
+    global.min_graduation_threshold = params.min_graduation_threshold;
+    global.max_graduation_threshold = params.max_graduation_threshold;
+    global.params_delay = params.params_delay;
+    global.price_oracle = params.price_oracle;
+    global.usd_graduation_threshold = params.usd_graduation_threshold;
+    global.oracle_max_age = params.oracle_max_age;
+    global.oracle_max_confidence_bps = params.oracle_max_confidence_bps;
+
+    msg!(
+        "Params set - feeRecipient: {}, ownerWallet: {}, feeAmount: {}, ammProgram: {}, tradeFeeBps: {}, referralFeeBps: {}, graduationMetric: {:?}, graduationThreshold: {}, minGraduationThreshold: {}, maxGraduationThreshold: {}, paramsDelay: {}, priceOracle: {}, usdGraduationThreshold: {}, oracleMaxAge: {}, oracleMaxConfidenceBps: {}",
         global.fee_recipient,
         global.owner_wallet,
-        global.agent_amount,
         global.fee_amount,
-        global.create_fee,
+        global.amm_program,
+        global.trade_fee_bps,
+        global.referral_fee_bps,
+        global.graduation_metric,
+        global.graduation_threshold,
+        global.min_graduation_threshold,
+        global.max_graduation_threshold,
+        global.params_delay,
+        global.price_oracle,
+        global.usd_graduation_threshold,
+        global.oracle_max_age,
+        global.oracle_max_confidence_bps,
     );
+}
 
-    Ok(())
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 1B tokens with 6 decimals
+    const SUPPLY: u64 = 1_000_000_000_000_000;
+
+    fn params() -> Params {
+        Params {
+            fee_recipient: Pubkey::new_unique(),
+            owner_wallet: Pubkey::new_unique(),
+            agent_amount: 0,
+            fee_amount: 1_000,
+            create_fee: 0,
+            amm_program: Pubkey::default(),
+            trade_fee_bps: 100,
+            referral_fee_bps: 2_000,
+            graduation_metric: GraduationMetric::MarketCap,
+            graduation_threshold: 100_000_000_000,
+            min_graduation_threshold: 1_000_000,
+            max_graduation_threshold: SUPPLY,
+            params_delay: 0,
+            price_oracle: Pubkey::default(),
+            usd_graduation_threshold: 0,
+            oracle_max_age: 0,
+            oracle_max_confidence_bps: 0,
+        }
+    }
+
+    fn rejects(change: impl FnOnce(&mut Params), code: F44Code) {
+        let mut params = params();
+        change(&mut params);
+        assert_eq!(validate_params(&params).unwrap_err(), code.into());
+    }
+
+    #[test]
+    fn accepts_sane_params_at_the_bounds() {
+        validate_params(&params()).unwrap();
+
+        let mut bounds = params();
+        bounds.fee_amount = MAX_FEE_AMOUNT;
+        bounds.trade_fee_bps = MAX_TRADE_FEE_BPS;
+        bounds.referral_fee_bps = BPS_DENOMINATOR;
+        bounds.graduation_metric = GraduationMetric::TokensSold;
+        bounds.min_graduation_threshold = SUPPLY;
+        bounds.graduation_threshold = SUPPLY;
+        bounds.params_delay = MAX_PARAMS_DELAY;
+        bounds.oracle_max_confidence_bps = BPS_DENOMINATOR;
+        validate_params(&bounds).unwrap();
+
+        // without a trading fee there is nothing to pay referrers from
+        let mut no_fee = params();
+        no_fee.trade_fee_bps = 0;
+        no_fee.referral_fee_bps = 0;
+        validate_params(&no_fee).unwrap();
+    }
+
+    #[test]
+    fn rejects_unset_accounts() {
+        rejects(|p| p.fee_recipient = Pubkey::default(), F44Code::UnValidFeeRecipient);
+        rejects(|p| p.owner_wallet = Pubkey::default(), F44Code::InvalidParams);
+        rejects(|p| p.amm_program = crate::ID, F44Code::InvalidAmmProgram);
+    }
+
+    #[test]
+    fn rejects_fees_out_of_range() {
+        rejects(|p| p.fee_amount = MAX_FEE_AMOUNT + 1, F44Code::InvalidParams);
+        rejects(|p| p.trade_fee_bps = MAX_TRADE_FEE_BPS + 1, F44Code::InvalidFeeBps);
+        rejects(|p| p.referral_fee_bps = BPS_DENOMINATOR + 1, F44Code::InvalidFeeBps);
+        rejects(|p| p.trade_fee_bps = 0, F44Code::InvalidFeeBps);
+        rejects(
+            |p| p.oracle_max_confidence_bps = BPS_DENOMINATOR + 1,
+            F44Code::InvalidFeeBps,
+        );
+    }
+
+    #[test]
+    fn rejects_thresholds_out_of_order() {
+        rejects(|p| p.min_graduation_threshold = 0, F44Code::InvalidGraduationThreshold);
+        rejects(
+            |p| p.min_graduation_threshold = p.graduation_threshold + 1,
+            F44Code::InvalidGraduationThreshold,
+        );
+        rejects(
+            |p| p.max_graduation_threshold = p.graduation_threshold - 1,
+            F44Code::InvalidGraduationThreshold,
+        );
+        // a market cap may go past the supply, a count of tokens sold may not
+        rejects(
+            |p| {
+                p.graduation_metric = GraduationMetric::TokensSold;
+                p.max_graduation_threshold = SUPPLY + 1;
+            },
+            F44Code::InvalidGraduationThreshold,
+        );
+    }
+
+    #[test]
+    fn rejects_a_delay_past_the_cap() {
+        rejects(|p| p.params_delay = MAX_PARAMS_DELAY + 1, F44Code::InvalidParamsDelay);
+    }
+}
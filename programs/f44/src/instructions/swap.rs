@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    constants::{BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED, POOL_SEED},
+    error::*,
+    events::*,
+    state::{BondingCurve, Global, Pool},
+    utils::{check_can_sell, check_f44_vault, check_not_paused, transfer_fee},
+};
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: Box<Account<'info, BondingCurve>>,
+
+    #[account(
+        mut,
+        seeds = [POOL_SEED, mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve,
+        token::token_program = token_program,
+    )]
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == pool.quote_mint @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = f44_mint,
+        associated_token::authority = user,
+        associated_token::token_program = f44_token_program,
+    )]
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub clock: Sysvar<'info, Clock>,
+}
+
+// Trades against the built-in pool of a graduated curve.
+// is_buy: F44 in and agent tokens out, otherwise agent tokens in and F44 out
+pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64, is_buy: bool) -> Result<()> {
+    let accts = ctx.accounts;
+
+    require!(amount_in > 0, F44Code::ZeroAmount);
+    // the pool keeps the curve's pause: buys stop, sells stay open while it is sell-only
+    if is_buy {
+        check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+    } else {
+        check_can_sell(&accts.global, &accts.bonding_curve)?;
+    }
+
+    let (amount_in, amount_out) = if is_buy {
+        // the pool is credited what the vault actually received
+        let vault_before = accts.f44_vault.amount;
+        let cpi_ctx = CpiContext::new(
+            accts.f44_token_program.to_account_info(),
+            TransferChecked {
+                from: accts.associated_user_f44_account.to_account_info().clone(),
+                mint: accts.f44_mint.to_account_info().clone(),
+                to: accts.f44_vault.to_account_info().clone(),
+                authority: accts.user.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount_in, accts.f44_mint.decimals)?;
+        accts.f44_vault.reload()?;
+        let f44_received = accts.f44_vault.amount - vault_before;
+
+        let token_out = accts.pool.swap_out(f44_received, true)?;
+        require!(token_out >= min_amount_out, F44Code::TooLittleOutput);
+
+        let binding = accts.mint.key();
+        let curve_seeds = &[BONDING_CURVE, binding.as_ref(), &[ctx.bumps.bonding_curve]];
+        let signer = &[&curve_seeds[..]];
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            TransferChecked {
+                from: accts.associated_bonding_curve.to_account_info().clone(),
+                mint: accts.mint.to_account_info().clone(),
+                to: accts.associated_user.to_account_info().clone(),
+                authority: accts.bonding_curve.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), token_out, accts.mint.decimals)?;
+
+        (f44_received, token_out)
+    } else {
+        let f44_out = accts.pool.swap_out(amount_in, false)?;
+        let f44_received = f44_out - transfer_fee(&accts.f44_mint.to_account_info(), f44_out)?;
+        require!(f44_received >= min_amount_out, F44Code::TooLittleOutput);
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program.to_account_info(),
+            TransferChecked {
+                from: accts.associated_user.to_account_info().clone(),
+                mint: accts.mint.to_account_info().clone(),
+                to: accts.associated_bonding_curve.to_account_info().clone(),
+                authority: accts.user.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount_in, accts.mint.decimals)?;
+
+        let global_seeds = &[GLOBAL_STATE_SEED, &[ctx.bumps.global]];
+        let signer = &[&global_seeds[..]];
+        let cpi_ctx = CpiContext::new(
+            accts.f44_token_program.to_account_info(),
+            TransferChecked {
+                from: accts.f44_vault.to_account_info().clone(),
+                mint: accts.f44_mint.to_account_info().clone(),
+                to: accts.associated_user_f44_account.to_account_info().clone(),
+                authority: accts.global.to_account_info().clone(),
+            },
+        );
+        transfer_checked(cpi_ctx.with_signer(signer), f44_out, accts.f44_mint.decimals)?;
+
+        (amount_in, f44_out)
+    };
+
+    accts.pool.apply_swap(amount_in, amount_out, is_buy)?;
+    accts.global.f44_supply = if is_buy {
+        accts.global.f44_supply.checked_add(amount_in)
+    } else {
+        accts.global.f44_supply.checked_sub(amount_out)
+    }
+    .ok_or(F44Code::MathOverflow)?;
+    accts.f44_vault.reload()?;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    msg!(
+        "SwapEvent - user: {}, mint: {}, pool: {}, isBuy: {}, amountIn: {}, amountOut: {}, f44Reserves: {}, tokenReserves: {}, timestamp: {}",
+        accts.user.key(),
+        accts.mint.key(),
+        accts.pool.key(),
+        is_buy,
+        amount_in,
+        amount_out,
+        accts.pool.f44_reserves,
+        accts.pool.token_reserves,
+        accts.clock.unix_timestamp,
+    );
+
+    emit!(SwapEvent {
+        user: accts.user.key(),
+        mint: accts.mint.key(),
+        pool: accts.pool.key(),
+        is_buy,
+        amount_in,
+        amount_out,
+        f44_reserves: accts.pool.f44_reserves,
+        token_reserves: accts.pool.token_reserves,
+        timestamp: accts.clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,311 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use std::mem::size_of;
+
+use crate::{
+    constants::{ADMIN_ACTION_SEED, ADMIN_SET_SEED, BONDING_CURVE, F44_VAULT_SEED, GLOBAL_STATE_SEED},
+    error::*,
+    instructions::check_authority,
+    math::{div_round, to_u64, Rounding},
+    state::{
+        AdminAction, AdminActionKind, AdminSet, BondingCurve, CurveKind, Global, GraduationMetric,
+        LegacyBondingCurve,
+    },
+    utils::check_f44_vault,
+};
+
+pub const LEGACY_BONDING_CURVE_SPACE: usize = 8 + size_of::<LegacyBondingCurve>();
+
+#[derive(Accounts)]
+pub struct UpgradeBondingCurve<'info> {
+    // upgraded by `upgrade_global` first
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: Box<Account<'info, Global>>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = f44_mint.key() == global.f44_mint @ F44Code::InvalidTokenAccount
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: still in the legacy f64 layout, so it can't be deserialized as `BondingCurve`.
+    /// The owner, discriminator and size are checked in the handler.
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE, mint.key().as_ref()],
+        bump
+    )]
+    pub bonding_curve: UncheckedAccount<'info>,
+
+    // a member of the admin set when it is the authority, see `check_authority`
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    // only when the admin set is the authority: its approved action for this call
+    #[account(
+        seeds = [ADMIN_SET_SEED],
+        bump
+    )]
+    pub admin_set: Option<Box<Account<'info, AdminSet>>>,
+
+    #[account(
+        mut,
+        seeds = [ADMIN_ACTION_SEED, &admin_action.nonce.to_le_bytes()],
+        bump
+    )]
+    pub admin_action: Option<Box<Account<'info, AdminAction>>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Rewrites a bonding curve created with the f64 layout into the fixed-point layout.
+// Float values are converted once here; all pricing afterwards is integer math.
+pub fn upgrade_bonding_curve(ctx: Context<UpgradeBondingCurve>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    check_authority(
+        &accts.global,
+        &accts.authority.key(),
+        accts.admin_set.as_deref(),
+        accts.admin_action.as_deref_mut(),
+        AdminActionKind::UpgradeBondingCurve {
+            mint: accts.mint.key(),
+        },
+    )?;
+
+    let curve_info = accts.bonding_curve.to_account_info();
+    require!(
+        curve_info.owner == ctx.program_id,
+        F44Code::InvalidCurveAccount
+    );
+
+    let legacy = {
+        let data = curve_info.try_borrow_data()?;
+        require!(
+            data.len() == LEGACY_BONDING_CURVE_SPACE,
+            F44Code::InvalidCurveAccount
+        );
+        require!(
+            data[..8] == BondingCurve::DISCRIMINATOR,
+            F44Code::InvalidCurveAccount
+        );
+        LegacyBondingCurve::deserialize(&mut &data[8..])?
+    };
+    require!(
+        legacy.token_mint == accts.mint.key(),
+        F44Code::MintDoesNotMatchBondingCurve
+    );
+
+    let token_decimals = i32::from(accts.mint.decimals);
+    let f44_decimals = i32::from(accts.f44_mint.decimals);
+
+    // Everything rounds down, so the curve is never credited more than it took in
+    let initial_price = legacy_price(legacy.initial_price, f44_decimals, token_decimals)?;
+    let curve_slope = legacy_price(legacy.curve_slope, f44_decimals, 2 * token_decimals)?;
+    let token_reserves = legacy_amount(legacy.token_reserves, token_decimals)?;
+    let token_total_supply = legacy_amount(legacy.token_total_supply, token_decimals)?;
+    let mcap_limit = legacy_amount(legacy.mcap_limit, f44_decimals)?;
+    require!(
+        token_reserves <= token_total_supply,
+        F44Code::InvalidReserves
+    );
+
+    // Legacy proceeds sat in the pooled supply, the curve is credited the area under it.
+    // What all curves are credited together never exceeds the legacy pooled supply.
+    let curve = CurveKind::Linear {
+        initial_price,
+        curve_slope,
+    };
+    let f44_reserves = if token_reserves > 0 {
+        curve
+            .proceeds_from_sell(token_reserves, token_reserves)?
+            .min(accts.global.legacy_f44_unassigned)
+    } else {
+        0
+    };
+    accts.global.legacy_f44_unassigned -= f44_reserves;
+    check_f44_vault(&mut accts.global, accts.f44_vault.amount)?;
+
+    let mut upgraded = BondingCurve {
+        curve,
+        token_reserves,
+        token_total_supply,
+        token_mint: legacy.token_mint,
+        quote_mint: accts.global.f44_mint,
+        f44_reserves,
+        graduation_metric: GraduationMetric::MarketCap,
+        graduation_threshold: mcap_limit,
+        current_price: 0,
+        current_mcap: 0,
+        complete: legacy.complete,
+        migrated: false,
+        creator: Pubkey::default(),
+        creator_fee_bps: 0,
+        creator_fees: 0,
+        protocol_fees: 0,
+        paused: false,
+        sell_only: false,
+    };
+    // Derived values are recomputed so they agree with the new integer math
+    upgraded.update_price()?;
+
+    // Grow the account and top up rent for the new layout
+    let new_space = 8 + size_of::<BondingCurve>();
+    let rent_due = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(curve_info.lamports());
+    if rent_due > 0 {
+        let cpi_ctx = CpiContext::new(
+            accts.system_program.to_account_info(),
+            Transfer {
+                from: accts.authority.to_account_info(),
+                to: curve_info.clone(),
+            },
+        );
+        transfer(cpi_ctx, rent_due)?;
+    }
+    curve_info.realloc(new_space, true)?;
+
+    let mut data = curve_info.try_borrow_mut_data()?;
+    upgraded.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Upgraded bonding curve {} - curve: {:?}, tokenReserves: {}, currentMcap: {}",
+        accts.bonding_curve.key(),
+        upgraded.curve,
+        upgraded.token_reserves,
+        upgraded.current_mcap,
+    );
+
+    Ok(())
+}
+
+// PRICE_SCALE is 10^18
+const PRICE_SCALE_DECIMALS: i32 = 18;
+
+// Converts a price in whole F44 per whole token (or per token squared for the slope)
+// into F44 base units per token base unit scaled by PRICE_SCALE, rounded down.
+fn legacy_price(value: f64, f44_decimals: i32, token_decimals: i32) -> Result<u128> {
+    legacy_fixed(
+        value,
+        f44_decimals + PRICE_SCALE_DECIMALS - token_decimals,
+        Rounding::Down,
+        F44Code::InvalidPrice,
+    )
+}
+
+// Converts a whole-unit amount into base units, rounded down.
+fn legacy_amount(value: f64, decimals: i32) -> Result<u64> {
+    to_u64(legacy_fixed(value, decimals, Rounding::Down, F44Code::InvalidAmount)?)
+}
+
+// `value * 10^exp10` in integers, exact up to the final rounding. The float is read as
+// mantissa * 2^exp2 from its bits and 10^n is split into 5^n * 2^n, so the only products
+// are the 53 bit mantissa with a power of five and shifts, all checked.
+fn legacy_fixed(value: f64, exp10: i32, rounding: Rounding, invalid: F44Code) -> Result<u128> {
+    // the legacy layout never held negative or non-finite values, -0.0 is still zero
+    if value == 0.0 {
+        return Ok(0);
+    }
+    if !value.is_finite() || value < 0.0 {
+        return Err(invalid.into());
+    }
+
+    let bits = value.to_bits();
+    let biased_exp = ((bits >> 52) & 0x7ff) as i32;
+    let fraction = u128::from(bits & ((1 << 52) - 1));
+    let (mantissa, exp2) = if biased_exp == 0 {
+        // subnormal
+        (fraction, -1074)
+    } else {
+        (fraction | 1 << 52, biased_exp - 1075)
+    };
+
+    let five = 5_u128
+        .checked_pow(exp10.unsigned_abs())
+        .ok_or(F44Code::MathOverflow)?;
+    let shift = exp2 + exp10;
+    if exp10 >= 0 {
+        let scaled = mantissa.checked_mul(five).ok_or(F44Code::MathOverflow)?;
+        return shift_round(scaled, shift, rounding);
+    }
+    // dividing by 5^n and by 2^k one after the other rounds the same as dividing once
+    if shift >= 0 {
+        div_round(shift_round(mantissa, shift, rounding)?, five, rounding)
+    } else {
+        shift_round(div_round(mantissa, five, rounding)?, shift, rounding)
+    }
+}
+
+// `value * 2^shift`, a negative shift divides with `rounding`.
+fn shift_round(value: u128, shift: i32, rounding: Rounding) -> Result<u128> {
+    if shift >= 0 {
+        let factor = 1_u128
+            .checked_shl(shift.unsigned_abs())
+            .ok_or(F44Code::MathOverflow)?;
+        return Ok(value.checked_mul(factor).ok_or(F44Code::MathOverflow)?);
+    }
+    let Some(divisor) = 1_u128.checked_shl(shift.unsigned_abs()) else {
+        // past 2^127 any u128 rounds to 0, or to 1 when rounding a non-zero value up
+        return Ok(u128::from(rounding == Rounding::Up && value > 0));
+    };
+    div_round(value, divisor, rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::PRICE_SCALE;
+
+    #[test]
+    fn price_scale_decimals_match() {
+        assert_eq!(10_u128.pow(PRICE_SCALE_DECIMALS as u32), PRICE_SCALE);
+    }
+
+    #[test]
+    fn converts_amounts_exactly() {
+        assert_eq!(legacy_amount(1.5, 6).unwrap(), 1_500_000);
+        assert_eq!(
+            legacy_amount(1_000_000_000.0, 6).unwrap(),
+            1_000_000_000_000_000
+        );
+        assert_eq!(legacy_amount(0.0, 6).unwrap(), 0);
+        assert_eq!(legacy_amount(-0.0, 6).unwrap(), 0);
+        // 0.1 is slightly above a tenth as a float, 1e-7 slightly below it
+        assert_eq!(legacy_amount(0.1, 1).unwrap(), 1);
+        assert_eq!(legacy_amount(1e-7, 7).unwrap(), 0);
+    }
+
+    #[test]
+    fn converts_prices_rounding_down() {
+        // 1 F44 per token at equal decimals is PRICE_SCALE
+        assert_eq!(legacy_price(1.0, 6, 6).unwrap(), PRICE_SCALE);
+        assert_eq!(legacy_price(0.25, 9, 6).unwrap(), PRICE_SCALE * 250);
+        // the float closest to 0.1 is 0.1000000000000000055..., truncated rather than rounded
+        assert_eq!(legacy_price(0.1, 6, 6).unwrap(), 100_000_000_000_000_005);
+        assert_eq!(legacy_price(f64::MIN_POSITIVE, 6, 6).unwrap(), 0);
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        assert!(legacy_amount(-1.0, 6).is_err());
+        assert!(legacy_amount(f64::NAN, 6).is_err());
+        assert!(legacy_price(f64::INFINITY, 6, 6).is_err());
+        assert!(legacy_amount(1e30, 6).is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{
+    system_program::{transfer, Transfer},
+    Discriminator,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use std::mem::size_of;
+
+use crate::{
+    constants::{DEFAULT_MCAP_LIMIT, F44_VAULT_SEED, GLOBAL_STATE_SEED},
+    error::*,
+    state::{Global, GraduationMetric, LegacyGlobal, Params},
+    utils::check_f44_vault,
+};
+
+pub const LEGACY_GLOBAL_SPACE: usize = 8 + size_of::<LegacyGlobal>();
+
+#[derive(Accounts)]
+pub struct UpgradeGlobal<'info> {
+    /// CHECK: still in the legacy layout, so it can't be deserialized as `Global`.
+    /// The owner, discriminator and size are checked in the handler.
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global: UncheckedAccount<'info>,
+
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
+        bump,
+    )]
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// Rewrites a `Global` created before the fixed-point switch into the current layout.
+// Has to run before `upgrade_bonding_curve`, which reads the new layout. Everything added
+// since starts the way `initialize` and `set_params` would leave it: no fees, the default
+// graduation threshold, no oracle, no timelock and not paused.
+pub fn upgrade_global(ctx: Context<UpgradeGlobal>) -> Result<()> {
+    let accts = ctx.accounts;
+
+    let global_info = accts.global.to_account_info();
+    require!(
+        global_info.owner == ctx.program_id,
+        F44Code::InvalidGlobalAccount
+    );
+
+    let legacy = {
+        let data = global_info.try_borrow_data()?;
+        require!(
+            data.len() == LEGACY_GLOBAL_SPACE,
+            F44Code::InvalidGlobalAccount
+        );
+        require!(
+            data[..8] == Global::DISCRIMINATOR,
+            F44Code::InvalidGlobalAccount
+        );
+        LegacyGlobal::deserialize(&mut &data[8..])?
+    };
+    require!(
+        legacy.authority == accts.authority.key(),
+        F44Code::NotAuthorized
+    );
+    require!(
+        legacy.f44_mint == accts.f44_mint.key() && legacy.f44_vault == accts.f44_vault.key(),
+        F44Code::InvalidTokenAccount
+    );
+
+    // the legacy vault held the pooled curve proceeds in `f44_supply` and the create fees
+    // on top, which are the protocol's now
+    let protocol_fees = accts.f44_vault.amount.saturating_sub(legacy.f44_supply);
+
+    let graduation_threshold = DEFAULT_MCAP_LIMIT
+        .checked_mul(
+            10_u64
+                .checked_pow(accts.f44_mint.decimals.into())
+                .ok_or(F44Code::MathOverflow)?,
+        )
+        .ok_or(F44Code::MathOverflow)?;
+    let params = Params {
+        fee_recipient: legacy.fee_recipient,
+        owner_wallet: legacy.owner_wallet,
+        agent_amount: legacy.agent_amount,
+        fee_amount: legacy.fee_amount,
+        create_fee: legacy.create_fee,
+        amm_program: Pubkey::default(),
+        trade_fee_bps: 0,
+        referral_fee_bps: 0,
+        graduation_metric: GraduationMetric::MarketCap,
+        graduation_threshold,
+        min_graduation_threshold: 1,
+        max_graduation_threshold: u64::MAX,
+        params_delay: 0,
+        price_oracle: Pubkey::default(),
+        usd_graduation_threshold: 0,
+        oracle_max_age: 0,
+        oracle_max_confidence_bps: 0,
+    };
+    let mut upgraded = Global {
+        initialized: legacy.initialized,
+        authority: legacy.authority,
+        pending_authority: Pubkey::default(),
+        fee_recipient: params.fee_recipient,
+        owner_wallet: params.owner_wallet,
+        f44_mint: legacy.f44_mint,
+        f44_vault: legacy.f44_vault,
+        f44_supply: legacy.f44_supply,
+        protocol_fees,
+        fee_amount: params.fee_amount,
+        agent_amount: params.agent_amount,
+        create_fee: params.create_fee,
+        trade_fee_bps: params.trade_fee_bps,
+        referral_fee_bps: params.referral_fee_bps,
+        amm_program: params.amm_program,
+        graduation_metric: params.graduation_metric,
+        graduation_threshold: params.graduation_threshold,
+        min_graduation_threshold: params.min_graduation_threshold,
+        max_graduation_threshold: params.max_graduation_threshold,
+        price_oracle: params.price_oracle,
+        usd_graduation_threshold: params.usd_graduation_threshold,
+        oracle_max_age: params.oracle_max_age,
+        oracle_max_confidence_bps: params.oracle_max_confidence_bps,
+        params_delay: params.params_delay,
+        pending_params: params,
+        pending_params_at: 0,
+        paused: false,
+        sell_only: false,
+        // handed out to the legacy curves by `upgrade_bonding_curve`
+        legacy_f44_unassigned: legacy.f44_supply,
+    };
+    check_f44_vault(&mut upgraded, accts.f44_vault.amount)?;
+
+    // Grow the account and top up rent for the new layout
+    let new_space = 8 + size_of::<Global>();
+    let rent_due = Rent::get()?
+        .minimum_balance(new_space)
+        .saturating_sub(global_info.lamports());
+    if rent_due > 0 {
+        let cpi_ctx = CpiContext::new(
+            accts.system_program.to_account_info(),
+            Transfer {
+                from: accts.authority.to_account_info(),
+                to: global_info.clone(),
+            },
+        );
+        transfer(cpi_ctx, rent_due)?;
+    }
+    global_info.realloc(new_space, true)?;
+
+    let mut data = global_info.try_borrow_mut_data()?;
+    upgraded.try_serialize(&mut &mut data[..])?;
+
+    msg!(
+        "Upgraded global {} - f44Supply: {}, protocolFees: {}",
+        accts.global.key(),
+        upgraded.f44_supply,
+        upgraded.protocol_fees,
+    );
+
+    Ok(())
+}
@@ -1,13 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint,Token,TokenAccount,Transfer, transfer}
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}
 };
 
 use crate::{
-    state::{Global, BondingCurve},
-    constants::{GLOBAL_STATE_SEED, BONDING_CURVE, F44_VAULT_SEED},
+    state::{Global, BondingCurve, QuoteConfig},
+    constants::{GLOBAL_STATE_SEED, BONDING_CURVE, F44_VAULT_SEED, QUOTE_CONFIG_SEED, SOL_VAULT_SEED},
     error::*,
+    events::WithdrawEvent,
+    utils::{check_not_paused, check_quote_config, check_quote_vault, check_sol_vault, quote_supply, transfer_lamports},
 };
 
 #[derive(Accounts)]
@@ -19,8 +21,11 @@ pub struct Withdraw<'info> {
     )]
     pub global: Account<'info, Global>,
     
-    #[account(mut)]
-    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        mint::token_program = token_program,
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
@@ -33,48 +38,86 @@ pub struct Withdraw<'info> {
         mut,
         token::mint = mint,
         token::authority = bonding_curve,
+        token::token_program = token_program,
     )]
-    pub associated_bonding_curve: Account<'info, TokenAccount>,
+    pub associated_bonding_curve: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
         payer = owner_wallet,
         associated_token::mint = mint,
-        associated_token::authority = owner_wallet
+        associated_token::authority = owner_wallet,
+        associated_token::token_program = token_program,
     )]
-    pub associated_user: Account<'info, TokenAccount>,
+    pub associated_user: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub f44_mint: Box<Account<'info, Mint>>,
+    // the curve's quote mint, F44 for SOL curves as those pay out of `sol_vault`
+    #[account(
+        mut,
+        mint::token_program = f44_token_program,
+        constraint = f44_mint.key() == if bonding_curve.is_sol_quoted() {
+            global.f44_mint
+        } else {
+            bonding_curve.quote_mint
+        } @ F44Code::InvalidQuoteMint
+    )]
+    pub f44_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
         seeds = [F44_VAULT_SEED, f44_mint.key().as_ref()],
         bump,
     )]
-    pub f44_vault: Box<Account<'info, TokenAccount>>,
+    pub f44_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // vault accounting of a whitelisted quote mint, only for curves quoted in one
+    #[account(
+        mut,
+        seeds = [QUOTE_CONFIG_SEED, f44_mint.key().as_ref()],
+        bump
+    )]
+    pub quote_config: Option<Box<Account<'info, QuoteConfig>>>,
 
     #[account(
         init_if_needed,
         payer = owner_wallet,
         associated_token::mint = f44_mint,
-        associated_token::authority = owner_wallet
+        associated_token::authority = owner_wallet,
+        associated_token::token_program = f44_token_program,
     )]
-    pub associated_user_f44_account: Box<Account<'info, TokenAccount>>,
+    pub associated_user_f44_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // lamport reserves of a SOL curve, required when the curve is quoted in SOL
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump,
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
     
     #[account(mut)]
     pub owner_wallet: Signer<'info>,
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub f44_token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
     let accts = ctx.accounts;
 
-    require!(accts.bonding_curve.complete == true, F44Code::BondingCurveNotComplete);
+    require!(accts.bonding_curve.complete, F44Code::BondingCurveNotComplete);
+    check_not_paused(&accts.global, Some(&accts.bonding_curve))?;
+    require!(!accts.bonding_curve.migrated, F44Code::AlreadyMigrated);
     require!(accts.global.owner_wallet == accts.owner_wallet.key(), F44Code::NotAuthorized);
-    // withdraw all SOL and rest tokens to the owner (temporary)
+    // an F44 curve's liquidity belongs to `migrate` or the built-in pool
+    require!(
+        accts.bonding_curve.quote_mint != accts.global.f44_mint,
+        F44Code::NotWithdrawable
+    );
+    // A SOL or whitelisted quote curve has no pool to graduate into, so withdraw settles it
+    // for good: the owner wallet takes every agent token left and the curve's whole reserves
+    // to list them off-program. Unclaimed creator and protocol fees stay to be claimed.
 
     let binding = accts.mint.key();
 
@@ -82,38 +125,92 @@ pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
     let vault_seeds = &[BONDING_CURVE, binding.as_ref(), &[bump]];
     let signer = &[&vault_seeds[..]];
 
-    
     let cpi_ctx = CpiContext::new(
         accts.token_program.to_account_info(),
-        Transfer {
+        TransferChecked {
             from: accts.associated_bonding_curve.to_account_info().clone(),
+            mint: accts.mint.to_account_info().clone(),
             to: accts.associated_user.to_account_info().clone(),
             authority: accts.bonding_curve.to_account_info().clone(),
         },
     );
-    transfer(
-        cpi_ctx.with_signer(signer),
-        (accts.bonding_curve.token_total_supply - accts.bonding_curve.token_reserves) as u64,
-    )?;
-
-    let (_, bump) =  Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
-    let global_seeds = &[GLOBAL_STATE_SEED, &[bump]];
-    let signer = &[&global_seeds[..]];
-
-    let cpi_ctx = CpiContext::new(
-        accts.token_program.to_account_info(),
-        Transfer {
-            from: accts.f44_vault.to_account_info().clone(),
-            to: accts.associated_user_f44_account.to_account_info().clone(),
-            authority: accts.global.to_account_info().clone(),
-        },
+    // sells burn tokens, so the vault holds less than total supply minus reserves
+    let token_amount = accts.associated_bonding_curve.amount;
+    transfer_checked(cpi_ctx.with_signer(signer), token_amount, accts.mint.decimals)?;
+
+    // only this curve's own reserves are paid out
+    let amount = accts.bonding_curve.f44_reserves;
+    accts.bonding_curve.f44_reserves = 0;
+    accts.bonding_curve.migrated = true;
+
+    if accts.bonding_curve.is_sol_quoted() {
+        let sol_vault = accts.sol_vault.as_ref().ok_or(F44Code::InvalidQuoteMint)?;
+        let sol_vault_seeds = &[SOL_VAULT_SEED, binding.as_ref(), &[ctx.bumps.sol_vault]];
+        transfer_lamports(
+            &accts.system_program.to_account_info(),
+            &sol_vault.to_account_info(),
+            &accts.owner_wallet.to_account_info(),
+            amount,
+            &[&sol_vault_seeds[..]],
+        )?;
+        check_sol_vault(&mut accts.bonding_curve, sol_vault.lamports())?;
+    } else {
+        check_quote_config(
+            &accts.global,
+            &accts.bonding_curve,
+            accts.quote_config.as_deref().map(|c| &**c),
+        )?;
+
+        let (_, bump) =  Pubkey::find_program_address(&[GLOBAL_STATE_SEED], ctx.program_id);
+        let global_seeds = &[GLOBAL_STATE_SEED, &[bump]];
+        let signer = &[&global_seeds[..]];
+
+        let cpi_ctx = CpiContext::new(
+            accts.f44_token_program.to_account_info(),
+            TransferChecked {
+                from: accts.f44_vault.to_account_info().clone(),
+                mint: accts.f44_mint.to_account_info().clone(),
+                to: accts.associated_user_f44_account.to_account_info().clone(),
+                authority: accts.global.to_account_info().clone(),
+            },
+        );
+        transfer_checked(
+            cpi_ctx.with_signer(signer),
+            amount,
+            accts.f44_mint.decimals,
+        )?;
+
+        let supply = quote_supply(&mut accts.global, accts.quote_config.as_deref_mut().map(|c| &mut **c));
+        *supply = supply.checked_sub(amount).ok_or(F44Code::MathOverflow)?;
+        accts.f44_vault.reload()?;
+        check_quote_vault(
+            &mut accts.global,
+            accts.quote_config.as_deref_mut().map(|c| &mut **c),
+            accts.f44_vault.amount,
+        )?;
+    }
+
+    let timestamp = Clock::get()?.unix_timestamp;
+    msg!(
+        "WithdrawEvent - ownerWallet: {}, mint: {}, bondingCurve: {}, quoteMint: {}, amount: {}, tokenAmount: {}, timestamp: {}",
+        accts.owner_wallet.key(),
+        accts.mint.key(),
+        accts.bonding_curve.key(),
+        accts.bonding_curve.quote_mint,
+        amount,
+        token_amount,
+        timestamp
     );
-    transfer(
-        cpi_ctx.with_signer(signer),
-        accts.global.f44_supply / 100,
-    )?;
 
-    accts.global.f44_supply -= accts.global.f44_supply / 100;
+    emit!(WithdrawEvent {
+        owner_wallet: accts.owner_wallet.key(),
+        mint: accts.mint.key(),
+        bonding_curve: accts.bonding_curve.key(),
+        quote_mint: accts.bonding_curve.quote_mint,
+        amount,
+        token_amount,
+        timestamp,
+    });
 
     Ok(())
 }
\ No newline at end of file
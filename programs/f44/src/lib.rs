@@ -3,6 +3,12 @@ pub mod error;
 pub mod instructions;
 pub mod state;
 pub mod events;
+pub mod math;
+pub mod curve;
+pub mod utils;
+pub mod amm;
+pub mod pool;
+pub mod oracle;
 
 use anchor_lang::prelude::*;
 
@@ -21,40 +27,274 @@ pub mod f44 {
         instructions::initialize(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn set_params(
         ctx: Context<SetParams>,
-        fee_recipient: Pubkey,
-        owner_wallet: Pubkey,
-        agent_amount: u64,
-        fee_amount: u64,
-        create_fee: u64,
+        fee_recipient: Option<Pubkey>,
+        owner_wallet: Option<Pubkey>,
+        fee_amount: Option<u64>,
+        amm_program: Option<Pubkey>,
+        trade_fee_bps: Option<u64>,
+        referral_fee_bps: Option<u64>,
+        graduation_metric: Option<GraduationMetric>,
+        graduation_threshold: Option<u64>,
+        min_graduation_threshold: Option<u64>,
+        max_graduation_threshold: Option<u64>,
+        params_delay: Option<u64>,
     ) -> Result<()> {
         instructions::set_params(
             ctx,
             fee_recipient,
             owner_wallet,
-            agent_amount,
             fee_amount,
+            amm_program,
+            trade_fee_bps,
+            referral_fee_bps,
+            graduation_metric,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
+            params_delay,
+        )
+    }
+
+    pub fn apply_pending_params(ctx: Context<ApplyPendingParams>) -> Result<()> {
+        instructions::apply_pending_params(ctx)
+    }
+
+    pub fn cancel_pending_params(ctx: Context<CancelPendingParams>) -> Result<()> {
+        instructions::cancel_pending_params(ctx)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        instructions::propose_authority(ctx, new_authority)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        instructions::accept_authority(ctx)
+    }
+
+    pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+        instructions::cancel_authority_transfer(ctx)
+    }
+
+    pub fn create_admin_set(ctx: Context<CreateAdminSet>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        instructions::create_admin_set(ctx, members, threshold)
+    }
+
+    pub fn propose_action(ctx: Context<ProposeAction>, kind: AdminActionKind) -> Result<()> {
+        instructions::propose_action(ctx, kind)
+    }
+
+    pub fn approve_action(ctx: Context<ApproveAction>) -> Result<()> {
+        instructions::approve_action(ctx)
+    }
+
+    pub fn execute_action(ctx: Context<ExecuteAction>) -> Result<()> {
+        instructions::execute_action(ctx)
+    }
+
+    pub fn set_global_pause(
+        ctx: Context<SetGlobalPause>,
+        paused: bool,
+        sell_only: bool,
+        reason: String,
+    ) -> Result<()> {
+        instructions::set_global_pause(ctx, paused, sell_only, reason)
+    }
+
+    pub fn set_curve_pause(
+        ctx: Context<SetCurvePause>,
+        paused: bool,
+        sell_only: bool,
+        reason: String,
+    ) -> Result<()> {
+        instructions::set_curve_pause(ctx, paused, sell_only, reason)
+    }
+
+    pub fn set_oracle(
+        ctx: Context<SetOracle>,
+        price_oracle: Pubkey,
+        usd_graduation_threshold: u64,
+        oracle_max_age: u64,
+        oracle_max_confidence_bps: u64,
+    ) -> Result<()> {
+        instructions::set_oracle(
+            ctx,
+            price_oracle,
+            usd_graduation_threshold,
+            oracle_max_age,
+            oracle_max_confidence_bps,
+        )
+    }
+
+    pub fn add_quote_mint(
+        ctx: Context<AddQuoteMint>,
+        create_fee: u64,
+        graduation_threshold: u64,
+        min_graduation_threshold: u64,
+        max_graduation_threshold: u64,
+    ) -> Result<()> {
+        instructions::add_quote_mint(
+            ctx,
             create_fee,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
         )
     }
 
+    pub fn update_quote_mint(
+        ctx: Context<UpdateQuoteMint>,
+        enabled: bool,
+        create_fee: u64,
+        graduation_threshold: u64,
+        min_graduation_threshold: u64,
+        max_graduation_threshold: u64,
+    ) -> Result<()> {
+        instructions::update_quote_mint(
+            ctx,
+            enabled,
+            create_fee,
+            graduation_threshold,
+            min_graduation_threshold,
+            max_graduation_threshold,
+        )
+    }
+
+    pub fn apply_quote_mint_update(ctx: Context<ApplyQuoteMintUpdate>) -> Result<()> {
+        instructions::apply_quote_mint_update(ctx)
+    }
+
+    pub fn cancel_quote_mint_update(ctx: Context<CancelQuoteMintUpdate>) -> Result<()> {
+        instructions::cancel_quote_mint_update(ctx)
+    }
+
+    pub fn push_price(ctx: Context<PushPrice>, price: i64, conf: u64, expo: i32) -> Result<()> {
+        instructions::push_price(ctx, price, conf, expo)
+    }
+
     pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
         instructions::deposit(ctx, amount)
     }
-    pub fn create(ctx: Context<Create>, initial_price: f64, curve_slope: f64, amount: u64) -> Result<()> {
-        instructions::create(ctx,initial_price, curve_slope, amount)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        ctx: Context<Create>,
+        curve: CurveKind,
+        mint_nonce: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+        creator_fee_bps: u64,
+        graduation_threshold: Option<u64>,
+    ) -> Result<()> {
+        instructions::create(
+            ctx,
+            curve,
+            mint_nonce,
+            name,
+            symbol,
+            uri,
+            creator_fee_bps,
+            graduation_threshold,
+        )
     }
 
     pub fn buy(ctx: Context<Buy>, amount: u64, max_f44_amount: u64) -> Result<()> {
         instructions::buy(ctx, amount, max_f44_amount)
     }
 
+    pub fn buy_exact_in(ctx: Context<Buy>, f44_in: u64, min_tokens_out: u64) -> Result<()> {
+        instructions::buy_exact_in(ctx, f44_in, min_tokens_out)
+    }
+
     pub fn sell(ctx: Context<Sell>, amount: u64, min_f44_output: u64) -> Result<()> {
         instructions::sell(ctx, amount, min_f44_output)
     }
 
+    pub fn sell_exact_out(ctx: Context<Sell>, f44_out: u64, max_tokens_in: u64) -> Result<()> {
+        instructions::sell_exact_out(ctx, f44_out, max_tokens_in)
+    }
+
+    pub fn buy_sol(ctx: Context<BuySol>, amount: u64, max_sol_amount: u64) -> Result<()> {
+        instructions::buy_sol(ctx, amount, max_sol_amount)
+    }
+
+    pub fn sell_sol(ctx: Context<SellSol>, amount: u64, min_sol_output: u64) -> Result<()> {
+        instructions::sell_sol(ctx, amount, min_sol_output)
+    }
+
+    pub fn buy_sol_exact_in(ctx: Context<BuySol>, sol_in: u64, min_tokens_out: u64) -> Result<()> {
+        instructions::buy_sol_exact_in(ctx, sol_in, min_tokens_out)
+    }
+
+    pub fn sell_sol_exact_out(ctx: Context<SellSol>, sol_out: u64, max_tokens_in: u64) -> Result<()> {
+        instructions::sell_sol_exact_out(ctx, sol_out, max_tokens_in)
+    }
+
     pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
         instructions::withdraw(ctx)
     }
+
+    pub fn upgrade_global(ctx: Context<UpgradeGlobal>) -> Result<()> {
+        instructions::upgrade_global(ctx)
+    }
+
+    pub fn upgrade_bonding_curve(ctx: Context<UpgradeBondingCurve>) -> Result<()> {
+        instructions::upgrade_bonding_curve(ctx)
+    }
+
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        instructions::migrate(ctx)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees(ctx)
+    }
+
+    pub fn claim_creator_fees_sol(ctx: Context<ClaimCreatorFeesSol>) -> Result<()> {
+        instructions::claim_creator_fees_sol(ctx)
+    }
+
+    pub fn claim_protocol_fees_sol(ctx: Context<ClaimProtocolFeesSol>) -> Result<()> {
+        instructions::claim_protocol_fees_sol(ctx)
+    }
+
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        instructions::claim_referral(ctx)
+    }
+
+    pub fn quote_buy(ctx: Context<Quote>, amount: u64) -> Result<BuyQuote> {
+        instructions::quote_buy(ctx, amount)
+    }
+
+    pub fn quote_sell(ctx: Context<Quote>, amount: u64) -> Result<SellQuote> {
+        instructions::quote_sell(ctx, amount)
+    }
+
+    pub fn quote_curve_state(ctx: Context<Quote>) -> Result<CurveStateQuote> {
+        instructions::quote_curve_state(ctx)
+    }
+
+    pub fn swap(ctx: Context<Swap>, amount_in: u64, min_amount_out: u64, is_buy: bool) -> Result<()> {
+        instructions::swap(ctx, amount_in, min_amount_out, is_buy)
+    }
+
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        shares: u64,
+        max_f44_amount: u64,
+        max_token_amount: u64,
+    ) -> Result<()> {
+        instructions::add_liquidity(ctx, shares, max_f44_amount, max_token_amount)
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        shares: u64,
+        min_f44_amount: u64,
+        min_token_amount: u64,
+    ) -> Result<()> {
+        instructions::remove_liquidity(ctx, shares, min_f44_amount, min_token_amount)
+    }
 }
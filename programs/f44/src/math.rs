@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::BPS_DENOMINATOR, error::F44Code};
+
+// Prices are stored as F44 base units per agent token base unit, scaled by PRICE_SCALE.
+pub const PRICE_SCALE: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Rounding {
+    // used for everything the user receives
+    Down,
+    // used for everything the user pays
+    Up,
+}
+
+pub fn div_round(numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    require!(denominator > 0, F44Code::InvalidCalculation);
+
+    let quotient = numerator / denominator;
+    // `is_multiple_of` is newer than the SBF toolchain's rustc
+    #[allow(clippy::manual_is_multiple_of)]
+    if rounding == Rounding::Up && numerator % denominator != 0 {
+        return quotient.checked_add(1).ok_or(error!(F44Code::MathOverflow));
+    }
+    Ok(quotient)
+}
+
+pub fn mul_div(a: u128, b: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    let product = a.checked_mul(b).ok_or(F44Code::MathOverflow)?;
+    div_round(product, denominator, rounding)
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(F44Code::MathOverflow))
+}
+
+// Market cap in F44 base units, rounded down.
+pub fn market_cap(price: u128, total_supply: u64) -> Result<u64> {
+    to_u64(mul_div(price, total_supply as u128, PRICE_SCALE, Rounding::Down)?)
+}
+
+// `bps` basis points of `amount`, rounded up in favour of whoever collects it.
+pub fn bps_fee(amount: u64, bps: u64) -> Result<u64> {
+    to_u64(mul_div(amount as u128, bps as u128, BPS_DENOMINATOR as u128, Rounding::Up)?)
+}
+
+// Integer square root, rounded down.
+pub fn sqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+// ln(2) scaled by PRICE_SCALE
+pub const LN_2: u128 = 693_147_180_559_945_309;
+// Largest exponent accepted by `exp`, keeps e^x * PRICE_SCALE well inside u128.
+pub const MAX_EXP_ARG: u128 = 40 * PRICE_SCALE;
+
+// e^(x / PRICE_SCALE) scaled by PRICE_SCALE, rounded down.
+// Range-reduced to 2^n * e^r with r in [0, ln 2) and a Taylor series for e^r.
+pub fn exp(x: u128) -> Result<u128> {
+    require!(x <= MAX_EXP_ARG, F44Code::MathOverflow);
+
+    let n = x / LN_2;
+    let r = x - n * LN_2;
+
+    let mut term = PRICE_SCALE;
+    let mut sum = PRICE_SCALE;
+    let mut i: u128 = 1;
+    while term > 0 {
+        term = term * r / (PRICE_SCALE * i);
+        sum += term;
+        i += 1;
+    }
+
+    Ok(sum << n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn div_round_rounds_in_the_requested_direction() {
+        assert_eq!(div_round(10, 3, Rounding::Down).unwrap(), 3);
+        assert_eq!(div_round(10, 3, Rounding::Up).unwrap(), 4);
+        // exact quotients are never bumped
+        assert_eq!(div_round(9, 3, Rounding::Up).unwrap(), 3);
+        assert_eq!(div_round(0, 3, Rounding::Up).unwrap(), 0);
+        assert!(div_round(1, 0, Rounding::Down).is_err());
+        assert_eq!(div_round(u128::MAX, 1, Rounding::Up).unwrap(), u128::MAX);
+    }
+
+    #[test]
+    fn mul_div_and_fees_round_against_the_user() {
+        assert_eq!(mul_div(7, 3, 2, Rounding::Down).unwrap(), 10);
+        assert_eq!(mul_div(7, 3, 2, Rounding::Up).unwrap(), 11);
+        assert!(mul_div(u128::MAX, 2, 1, Rounding::Down).is_err());
+        // 1% of 1 base unit is still charged
+        assert_eq!(bps_fee(1, 100).unwrap(), 1);
+        assert_eq!(bps_fee(10_000, 100).unwrap(), 100);
+        assert_eq!(bps_fee(10_000, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn sqrt_rounds_down_at_the_bounds() {
+        assert_eq!(sqrt(0), 0);
+        assert_eq!(sqrt(1), 1);
+        assert_eq!(sqrt(2), 1);
+        assert_eq!(sqrt(3), 1);
+        assert_eq!(sqrt(4), 2);
+        assert_eq!(sqrt(99), 9);
+        assert_eq!(sqrt(100), 10);
+        assert_eq!(sqrt(u64::MAX as u128 * u64::MAX as u128), u64::MAX as u128);
+        assert_eq!(sqrt(u128::MAX), u64::MAX as u128);
+    }
+
+    #[test]
+    fn exp_matches_known_values() {
+        // within 1e-12 relative of the exact value
+        let close = |actual: u128, expected: u128| {
+            let diff = actual.abs_diff(expected);
+            assert!(diff <= expected / 1_000_000_000_000, "{actual} vs {expected}");
+        };
+        assert_eq!(exp(0).unwrap(), PRICE_SCALE);
+        close(exp(PRICE_SCALE).unwrap(), 2_718_281_828_459_045_235);
+        close(exp(LN_2).unwrap(), 2 * PRICE_SCALE);
+        close(exp(10 * LN_2).unwrap(), 1024 * PRICE_SCALE);
+        // e^40
+        close(exp(MAX_EXP_ARG).unwrap(), 235_385_266_837_019_985_407_899_910_749_034_804);
+        assert!(exp(MAX_EXP_ARG + 1).is_err());
+    }
+
+    #[test]
+    fn exp_is_monotonic() {
+        let mut previous = exp(0).unwrap();
+        for i in 1..=200u128 {
+            let current = exp(i * PRICE_SCALE / 5).unwrap();
+            assert!(current > previous);
+            previous = current;
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::BPS_DENOMINATOR,
+    math::{div_round, Rounding},
+    state::PriceFeed,
+};
+
+// Pyth v2 price account layout, only the fields read here.
+const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+const PYTH_PRICE_ACCOUNT: u32 = 3;
+const PYTH_STATUS_TRADING: u32 = 1;
+const PYTH_ATYPE_OFFSET: usize = 8;
+const PYTH_EXPO_OFFSET: usize = 20;
+const PYTH_TIMESTAMP_OFFSET: usize = 96;
+const PYTH_PRICE_OFFSET: usize = 208;
+const PYTH_CONF_OFFSET: usize = 216;
+const PYTH_STATUS_OFFSET: usize = 224;
+
+// USD price of one whole F44, price * 10^expo, with its confidence interval.
+// Everything here returns None for a price that cannot be trusted, so callers can fall back.
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    // Reads the program's own `PriceFeed`, or else a Pyth price account.
+    pub fn load(info: &AccountInfo) -> Option<Self> {
+        let data = info.try_borrow_data().ok()?;
+        if *info.owner == crate::ID {
+            let feed = PriceFeed::try_deserialize(&mut &data[..]).ok()?;
+            return Some(OraclePrice {
+                price: feed.price,
+                conf: feed.conf,
+                expo: feed.expo,
+                publish_time: feed.publish_time,
+            });
+        }
+
+        if read_u32(&data, 0)? != PYTH_MAGIC
+            || read_u32(&data, PYTH_ATYPE_OFFSET)? != PYTH_PRICE_ACCOUNT
+            || read_u32(&data, PYTH_STATUS_OFFSET)? != PYTH_STATUS_TRADING
+        {
+            return None;
+        }
+        Some(OraclePrice {
+            price: read_i64(&data, PYTH_PRICE_OFFSET)?,
+            conf: read_i64(&data, PYTH_CONF_OFFSET)? as u64,
+            expo: read_u32(&data, PYTH_EXPO_OFFSET)? as i32,
+            publish_time: read_i64(&data, PYTH_TIMESTAMP_OFFSET)?,
+        })
+    }
+
+    // Rejects prices older than `max_age` seconds or with a confidence interval wider
+    // than `max_confidence_bps` of the price.
+    pub fn checked(self, now: i64, max_age: u64, max_confidence_bps: u64) -> Option<Self> {
+        if self.price <= 0 || now.saturating_sub(self.publish_time) > max_age as i64 {
+            return None;
+        }
+        let conf = self.conf as u128 * BPS_DENOMINATOR as u128;
+        if conf > self.price as u128 * max_confidence_bps as u128 {
+            return None;
+        }
+        Some(self)
+    }
+
+    // F44 base units worth `usd` whole dollars, rounded up.
+    pub fn f44_for_usd(&self, usd: u64, f44_decimals: u8) -> Option<u64> {
+        let (numerator_exp, denominator_exp) = if self.expo < 0 {
+            (f44_decimals as u32 + self.expo.unsigned_abs(), 0)
+        } else {
+            (f44_decimals as u32, self.expo as u32)
+        };
+        let numerator = (usd as u128).checked_mul(10u128.checked_pow(numerator_exp)?)?;
+        let denominator = (self.price as u128).checked_mul(10u128.checked_pow(denominator_exp)?)?;
+        let amount = div_round(numerator, denominator, Rounding::Up).ok()?;
+        u64::try_from(amount).ok()
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> Option<i64> {
+    Some(i64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a trading Pyth price account, $0.50 +- $0.001 at 10^-8
+    fn pyth_account() -> Vec<u8> {
+        let mut data = vec![0_u8; 240];
+        data[0..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[PYTH_ATYPE_OFFSET..][..4].copy_from_slice(&PYTH_PRICE_ACCOUNT.to_le_bytes());
+        data[PYTH_EXPO_OFFSET..][..4].copy_from_slice(&(-8_i32).to_le_bytes());
+        data[PYTH_TIMESTAMP_OFFSET..][..8].copy_from_slice(&1_000_i64.to_le_bytes());
+        data[PYTH_PRICE_OFFSET..][..8].copy_from_slice(&50_000_000_i64.to_le_bytes());
+        data[PYTH_CONF_OFFSET..][..8].copy_from_slice(&100_000_i64.to_le_bytes());
+        data[PYTH_STATUS_OFFSET..][..4].copy_from_slice(&PYTH_STATUS_TRADING.to_le_bytes());
+        data
+    }
+
+    fn load(owner: Pubkey, mut data: Vec<u8>) -> Option<OraclePrice> {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        OraclePrice::load(&info)
+    }
+
+    fn price(price: i64, conf: u64, expo: i32, publish_time: i64) -> OraclePrice {
+        OraclePrice {
+            price,
+            conf,
+            expo,
+            publish_time,
+        }
+    }
+
+    #[test]
+    fn reads_a_pyth_price_account() {
+        let price = load(Pubkey::new_unique(), pyth_account()).unwrap();
+        assert_eq!(price.price, 50_000_000);
+        assert_eq!(price.conf, 100_000);
+        assert_eq!(price.expo, -8);
+        assert_eq!(price.publish_time, 1_000);
+    }
+
+    #[test]
+    fn rejects_anything_but_a_trading_pyth_price() {
+        for offset in [0, PYTH_ATYPE_OFFSET, PYTH_STATUS_OFFSET] {
+            let mut data = pyth_account();
+            data[offset] ^= 0xff;
+            assert!(load(Pubkey::new_unique(), data).is_none());
+        }
+        // cut short before the status
+        let mut data = pyth_account();
+        data.truncate(PYTH_STATUS_OFFSET + 2);
+        assert!(load(Pubkey::new_unique(), data).is_none());
+    }
+
+    #[test]
+    fn reads_the_programs_own_price_feed() {
+        let feed = PriceFeed {
+            price: 12_345,
+            conf: 6,
+            expo: -4,
+            publish_time: 77,
+        };
+        let mut data = Vec::new();
+        feed.try_serialize(&mut data).unwrap();
+
+        let price = load(crate::ID, data.clone()).unwrap();
+        assert_eq!(price.price, 12_345);
+        assert_eq!(price.conf, 6);
+        assert_eq!(price.expo, -4);
+        assert_eq!(price.publish_time, 77);
+        // a feed's layout is only trusted from the program itself
+        assert!(load(Pubkey::new_unique(), data).is_none());
+    }
+
+    #[test]
+    fn checked_drops_stale_uncertain_and_non_positive_prices() {
+        assert!(price(50_000_000, 100_000, -8, 1_000).checked(1_060, 60, 20).is_some());
+        assert!(price(50_000_000, 100_000, -8, 1_000).checked(1_061, 60, 20).is_none());
+        // 100_000 is 20 bps of the price
+        assert!(price(50_000_000, 100_000, -8, 1_000).checked(1_000, 60, 19).is_none());
+        assert!(price(0, 0, -8, 1_000).checked(1_000, 60, 20).is_none());
+        assert!(price(-1, 0, -8, 1_000).checked(1_000, 60, 20).is_none());
+    }
+
+    #[test]
+    fn f44_for_usd_rounds_up() {
+        // $0.50 per F44 with 6 decimals: $100 is 200 F44
+        let half = price(50_000_000, 0, -8, 0);
+        assert_eq!(half.f44_for_usd(100, 6), Some(200_000_000));
+        // $3 per F44, a third of a base unit is rounded up
+        let three = price(3, 0, 0, 0);
+        assert_eq!(three.f44_for_usd(1, 6), Some(333_334));
+        // a positive exponent scales the price up
+        let thousand = price(1, 0, 3, 0);
+        assert_eq!(thousand.f44_for_usd(1_000, 6), Some(1_000_000));
+        // out of range instead of wrapping
+        assert_eq!(price(1, 0, -30, 0).f44_for_usd(u64::MAX, 9), None);
+    }
+}
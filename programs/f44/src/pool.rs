@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BPS_DENOMINATOR, POOL_FEE_BPS},
+    error::F44Code,
+    math::{mul_div, sqrt, to_u64, Rounding},
+    state::Pool,
+};
+
+// Constant-product math for the built-in pool. Like the curve, everything the user
+// receives rounds down and everything the user pays rounds up.
+impl Pool {
+    // Starts the pool from a graduated curve. The initial shares are locked for good.
+    pub fn seed(
+        &mut self,
+        token_mint: Pubkey,
+        quote_mint: Pubkey,
+        f44_amount: u64,
+        token_amount: u64,
+    ) -> Result<()> {
+        require!(
+            f44_amount > 0 && token_amount > 0,
+            F44Code::InsufficientLiquidity
+        );
+        let shares = to_u64(sqrt(f44_amount as u128 * token_amount as u128))?;
+
+        self.token_mint = token_mint;
+        self.quote_mint = quote_mint;
+        self.f44_reserves = f44_amount;
+        self.token_reserves = token_amount;
+        self.lp_supply = shares;
+        self.locked_lp = shares;
+        Ok(())
+    }
+
+    // Output of selling `amount_in` into the pool. F44 goes in when `is_buy`.
+    pub fn swap_out(&self, amount_in: u64, is_buy: bool) -> Result<u64> {
+        require!(amount_in > 0, F44Code::ZeroAmount);
+        let (reserve_in, reserve_out) = self.sides(is_buy);
+
+        let in_after_fee = mul_div(
+            amount_in as u128,
+            (BPS_DENOMINATOR - POOL_FEE_BPS) as u128,
+            BPS_DENOMINATOR as u128,
+            Rounding::Down,
+        )?;
+        let amount_out = mul_div(
+            reserve_out as u128,
+            in_after_fee,
+            reserve_in as u128 + in_after_fee,
+            Rounding::Down,
+        )?;
+        require!(
+            amount_out > 0 && amount_out < reserve_out as u128,
+            F44Code::InsufficientLiquidity
+        );
+        to_u64(amount_out)
+    }
+
+    pub fn apply_swap(&mut self, amount_in: u64, amount_out: u64, is_buy: bool) -> Result<()> {
+        let (reserve_in, reserve_out) = if is_buy {
+            (&mut self.f44_reserves, &mut self.token_reserves)
+        } else {
+            (&mut self.token_reserves, &mut self.f44_reserves)
+        };
+        *reserve_in = reserve_in
+            .checked_add(amount_in)
+            .ok_or(F44Code::MathOverflow)?;
+        *reserve_out = reserve_out
+            .checked_sub(amount_out)
+            .ok_or(F44Code::InsufficientLiquidity)?;
+        Ok(())
+    }
+
+    // F44 and agent tokens needed to mint `shares`.
+    pub fn amounts_for_shares(&self, shares: u64, rounding: Rounding) -> Result<(u64, u64)> {
+        require!(shares > 0, F44Code::ZeroAmount);
+        let f44_amount = mul_div(
+            self.f44_reserves as u128,
+            shares as u128,
+            self.lp_supply as u128,
+            rounding,
+        )?;
+        let token_amount = mul_div(
+            self.token_reserves as u128,
+            shares as u128,
+            self.lp_supply as u128,
+            rounding,
+        )?;
+        Ok((to_u64(f44_amount)?, to_u64(token_amount)?))
+    }
+
+    pub fn add_liquidity(&mut self, shares: u64, f44_amount: u64, token_amount: u64) -> Result<()> {
+        self.f44_reserves = self
+            .f44_reserves
+            .checked_add(f44_amount)
+            .ok_or(F44Code::MathOverflow)?;
+        self.token_reserves = self
+            .token_reserves
+            .checked_add(token_amount)
+            .ok_or(F44Code::MathOverflow)?;
+        self.lp_supply = self
+            .lp_supply
+            .checked_add(shares)
+            .ok_or(F44Code::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn remove_liquidity(&mut self, shares: u64, f44_amount: u64, token_amount: u64) -> Result<()> {
+        self.f44_reserves = self
+            .f44_reserves
+            .checked_sub(f44_amount)
+            .ok_or(F44Code::InsufficientLiquidity)?;
+        self.token_reserves = self
+            .token_reserves
+            .checked_sub(token_amount)
+            .ok_or(F44Code::InsufficientLiquidity)?;
+        self.lp_supply = self
+            .lp_supply
+            .checked_sub(shares)
+            .ok_or(F44Code::InsufficientShares)?;
+        Ok(())
+    }
+
+    fn sides(&self, is_buy: bool) -> (u64, u64) {
+        if is_buy {
+            (self.f44_reserves, self.token_reserves)
+        } else {
+            (self.token_reserves, self.f44_reserves)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(f44_amount: u64, token_amount: u64) -> Pool {
+        let mut pool = Pool {
+            token_mint: Pubkey::default(),
+            quote_mint: Pubkey::default(),
+            f44_reserves: 0,
+            token_reserves: 0,
+            lp_supply: 0,
+            locked_lp: 0,
+        };
+        pool.seed(Pubkey::new_unique(), Pubkey::new_unique(), f44_amount, token_amount)
+            .unwrap();
+        pool
+    }
+
+    fn k(pool: &Pool) -> u128 {
+        pool.f44_reserves as u128 * pool.token_reserves as u128
+    }
+
+    #[test]
+    fn seeding_locks_the_geometric_mean_in_shares() {
+        let seeded = pool(4_000_000, 9_000_000);
+        assert_eq!(seeded.lp_supply, 6_000_000);
+        assert_eq!(seeded.locked_lp, seeded.lp_supply);
+        // rounded down when the product is not a square
+        assert_eq!(pool(2, 1).lp_supply, 1);
+    }
+
+    #[test]
+    fn seeding_needs_both_sides() {
+        let mut seeded = pool(1, 1);
+        assert!(seeded.seed(Pubkey::default(), Pubkey::default(), 0, 1).is_err());
+        assert!(seeded.seed(Pubkey::default(), Pubkey::default(), 1, 0).is_err());
+    }
+
+    #[test]
+    fn swaps_take_the_fee_and_never_shrink_k() {
+        let mut pool = pool(10_000_000_000, 990_000_000_000_000);
+        for (amount_in, is_buy) in [
+            (1_000_000, true),
+            (123_456_789_000, false),
+            (999, true),
+            (5_000_000_000, true),
+            (10_000_000_000_000, false),
+        ] {
+            let before = k(&pool);
+            let amount_out = pool.swap_out(amount_in, is_buy).unwrap();
+            // no better than the fee-free price
+            let (reserve_in, reserve_out) = pool.sides(is_buy);
+            let fee_free =
+                reserve_out as u128 * amount_in as u128 / (reserve_in as u128 + amount_in as u128);
+            assert!((amount_out as u128) < fee_free);
+
+            pool.apply_swap(amount_in, amount_out, is_buy).unwrap();
+            assert!(k(&pool) >= before);
+        }
+    }
+
+    #[test]
+    fn swaps_never_drain_a_side() {
+        let pool = pool(1_000, 1_000);
+        // too small to come out as a single unit
+        assert!(pool.swap_out(1, true).is_err());
+        assert!(pool.swap_out(0, true).is_err());
+        // a huge trade still leaves something behind
+        let amount_out = pool.swap_out(u64::MAX / 2, false).unwrap();
+        assert!(amount_out < pool.f44_reserves);
+    }
+
+    #[test]
+    fn shares_round_in_favour_of_the_pool() {
+        let mut pool = pool(1_000_000_007, 3_000_000_011);
+        let shares = pool.lp_supply / 3;
+
+        let (f44_in, token_in) = pool.amounts_for_shares(shares, Rounding::Up).unwrap();
+        let (f44_out, token_out) = pool.amounts_for_shares(shares, Rounding::Down).unwrap();
+        assert!(f44_in >= f44_out && token_in >= token_out);
+        assert!(f44_in - f44_out <= 1 && token_in - token_out <= 1);
+
+        // adding and removing the same shares leaves the pool at least as rich
+        pool.add_liquidity(shares, f44_in, token_in).unwrap();
+        let (f44_out, token_out) = pool.amounts_for_shares(shares, Rounding::Down).unwrap();
+        pool.remove_liquidity(shares, f44_out, token_out).unwrap();
+        assert!(pool.f44_reserves >= 1_000_000_007);
+        assert!(pool.token_reserves >= 3_000_000_011);
+        assert!(pool.amounts_for_shares(0, Rounding::Down).is_err());
+    }
+
+    #[test]
+    fn removing_more_than_the_pool_holds_fails() {
+        let mut pool = pool(1_000, 1_000);
+        assert!(pool.remove_liquidity(1, 1_001, 0).is_err());
+        assert!(pool.remove_liquidity(pool.lp_supply + 1, 0, 0).is_err());
+    }
+}
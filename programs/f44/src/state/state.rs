@@ -1,21 +1,416 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::MAX_ADMIN_MEMBERS;
+
 #[account]
 pub struct Global {
     pub initialized: bool,
     pub authority: Pubkey,
+    // proposed by the authority and in charge once it accepts, the default pubkey when none
+    pub pending_authority: Pubkey,
     pub fee_recipient: Pubkey,
     pub owner_wallet: Pubkey,
     pub f44_mint: Pubkey,
     pub f44_vault: Pubkey,
+    // F44 owned by bonding curves and built-in pools (their `f44_reserves`) plus unclaimed
+    // creator fees and referral rewards
     pub f44_supply: u64,
+    // create fees, owner deposits and F44 sent to the vault directly, not owned by any curve
+    pub protocol_fees: u64,
+    // create fee of curves without a quote config, in whole F44 tokens
     pub fee_amount: u64,
+    // deprecated and unused, kept so the account layout doesn't change
     pub agent_amount: u64,
+    // deprecated and unused, `create` charges `fee_amount`
     pub create_fee: u64,
+    // share of every curve buy and sell paid to `fee_recipient`
+    pub trade_fee_bps: u64,
+    // part of the trading fee credited to the referrer of a trade, in bps of the fee
+    pub referral_fee_bps: u64,
+    // AMM that `migrate` seeds a pool on once a curve completes
+    pub amm_program: Pubkey,
+    // graduation condition new curves start with, creators may pick a threshold within the bounds
+    pub graduation_metric: GraduationMetric,
+    pub graduation_threshold: u64,
+    pub min_graduation_threshold: u64,
+    pub max_graduation_threshold: u64,
+    // USD price account of F44, a Pyth price account or the program's own `PriceFeed`.
+    // While set, curves graduate once the F44 they raised is worth `usd_graduation_threshold`
+    // whole dollars, and fall back to their own threshold when the price is stale or uncertain.
+    pub price_oracle: Pubkey,
+    pub usd_graduation_threshold: u64,
+    // seconds a price stays usable
+    pub oracle_max_age: u64,
+    // widest accepted confidence interval, in bps of the price
+    pub oracle_max_confidence_bps: u64,
+    // seconds a `set_params`, `set_oracle` or `update_quote_mint` change waits before it can be applied
+    pub params_delay: u64,
+    // queued change and when it can be applied, `pending_params_at` is 0 when none is queued
+    pub pending_params: Params,
+    pub pending_params_at: i64,
+    // halts buys, sells, creates and withdraws everywhere, sells go on while `sell_only` is set
+    pub paused: bool,
+    pub sell_only: bool,
+    // F44 of a legacy deployment's pooled `f44_supply` not yet credited to an upgraded curve
+    pub legacy_f44_unassigned: u64,
 }
 
+// Prices are quote base units per agent token base unit scaled by `PRICE_SCALE`,
+// token amounts are agent token base units and market caps are quote base units.
+// The quote is F44 unless `quote_mint` says otherwise.
 #[account]
 pub struct BondingCurve {
+    pub curve: CurveKind,
+    pub token_reserves: u64,
+    pub token_total_supply: u64,
+    pub token_mint: Pubkey,
+    // asset the curve trades against: the F44 mint, the native mint for SOL curves or a
+    // mint with a `QuoteConfig`
+    pub quote_mint: Pubkey,
+    // quote paid into this curve and still held for it, in the vault of the quote mint
+    // or as lamports in the curve's SOL vault
+    pub f44_reserves: u64,
+    // the curve completes once its metric passes the threshold
+    pub graduation_metric: GraduationMetric,
+    pub graduation_threshold: u64,
+    pub current_price: u128,
+    pub current_mcap: u64,
+    pub complete: bool,
+    // the liquidity left the curve: migrated, seeded into the pool or withdrawn
+    pub migrated: bool,
+    // launcher of the curve and their share of every buy and sell
+    pub creator: Pubkey,
+    pub creator_fee_bps: u64,
+    // accrued creator fees, held with the reserves until claimed
+    pub creator_fees: u64,
+    // trading fees of a SOL curve, held in its SOL vault until the fee recipient claims them
+    pub protocol_fees: u64,
+    // same as the global switch, for this curve only
+    pub paused: bool,
+    pub sell_only: bool,
+}
+
+// Built-in x*y=k pool a curve graduates into when no external AMM is configured.
+// Its reserves stay in the curve vaults: agent tokens in the bonding curve vault and
+// F44 in the shared F44 vault, counted in `Global.f44_supply`.
+#[account]
+pub struct Pool {
+    pub token_mint: Pubkey,
+    // the quote the curve graduated with, the only mint its vault side may be paid in
+    pub quote_mint: Pubkey,
+    pub f44_reserves: u64,
+    pub token_reserves: u64,
+    pub lp_supply: u64,
+    // shares minted for the graduated liquidity, owned by nobody
+    pub locked_lp: u64,
+}
+
+// A token mint besides F44 and SOL that curves may be launched against. Its vault is the
+// token account at [F44_VAULT_SEED, quote_mint] and is accounted here the way `Global`
+// accounts for the F44 vault.
+#[account]
+pub struct QuoteConfig {
+    pub quote_mint: Pubkey,
+    pub vault: Pubkey,
+    // only stops new launches, curves already quoted in the mint keep trading
+    pub enabled: bool,
+    // create fee in quote base units, paid into the vault
+    pub create_fee: u64,
+    // graduation threshold new curves start with and the bounds creators may pick from
+    pub graduation_threshold: u64,
+    pub min_graduation_threshold: u64,
+    pub max_graduation_threshold: u64,
+    // quote owned by curves plus their unclaimed creator fees
+    pub supply: u64,
+    // create fees and quote sent to the vault directly, not owned by any curve
+    pub protocol_fees: u64,
+    // change queued by `update_quote_mint`, applied after the global `params_delay`
+    pub pending_params: QuoteParams,
+    // when the queued change may be applied, 0 when none is queued
+    pub pending_params_at: i64,
+}
+
+// What `update_quote_mint` changes on a `QuoteConfig`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct QuoteParams {
+    pub enabled: bool,
+    pub create_fee: u64,
+    pub graduation_threshold: u64,
+    pub min_graduation_threshold: u64,
+    pub max_graduation_threshold: u64,
+}
+
+// M-of-N multisig of admins. Once it is the global authority, admin changes go through
+// `propose_action`, `approve_action` and `execute_action`.
+#[account]
+pub struct AdminSet {
+    // only the first `member_count` entries are members
+    pub members: [Pubkey; MAX_ADMIN_MEMBERS],
+    pub member_count: u8,
+    pub threshold: u8,
+    // nonce of the next proposed action, part of its PDA seeds
+    pub action_count: u64,
+    // actions proposed before the members last changed are void, their approval bits
+    // point at the old member list
+    pub first_valid_action: u64,
+}
+
+impl AdminSet {
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members[..self.member_count as usize]
+            .iter()
+            .position(|member| member == key)
+    }
+}
+
+// An admin change waiting for approvals, executed at most once.
+#[account]
+pub struct AdminAction {
+    pub nonce: u64,
+    pub proposer: Pubkey,
+    pub action: AdminActionKind,
+    // bit i is set once `members[i]` approved
+    pub approvals: u16,
+    pub executed: bool,
+}
+
+// not boxed: the action account is sized with `size_of`, which must see the whole update
+#[allow(clippy::large_enum_variant)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
+pub enum AdminActionKind {
+    SetParams { update: ParamsUpdate },
+    ProposeAuthority { new_authority: Pubkey },
+    // takes over as authority after the current one proposed the admin set
+    AcceptAuthority,
+    CancelAuthorityTransfer,
+    CancelPendingParams,
+    // emergency: stops USD graduation when the oracle misbehaves
+    DisableOracle,
+    // emergency: the global pause switch, see `set_global_pause`
+    SetGlobalPause {
+        paused: bool,
+        sell_only: bool,
+        reason: String,
+    },
+    // price_oracle set to the default pubkey turns USD graduation off, see `set_oracle`
+    SetOracle {
+        price_oracle: Pubkey,
+        usd_graduation_threshold: u64,
+        oracle_max_age: u64,
+        oracle_max_confidence_bps: u64,
+    },
+    // replaces the members and threshold, pending actions are void afterwards
+    UpdateAdminSet {
+        members: [Pubkey; MAX_ADMIN_MEMBERS],
+        member_count: u8,
+        threshold: u8,
+    },
+    // The rest need accounts `execute_action` doesn't have. A member calls the instruction
+    // itself and passes the approved action in place of the authority's signature.
+    SetCurvePause {
+        mint: Pubkey,
+        paused: bool,
+        sell_only: bool,
+        reason: String,
+    },
+    PushPrice {
+        price: i64,
+        conf: u64,
+        expo: i32,
+    },
+    AddQuoteMint {
+        quote_mint: Pubkey,
+        create_fee: u64,
+        graduation_threshold: u64,
+        min_graduation_threshold: u64,
+        max_graduation_threshold: u64,
+    },
+    UpdateQuoteMint {
+        quote_mint: Pubkey,
+        enabled: bool,
+        create_fee: u64,
+        graduation_threshold: u64,
+        min_graduation_threshold: u64,
+        max_graduation_threshold: u64,
+    },
+    CancelQuoteMintUpdate {
+        quote_mint: Pubkey,
+    },
+    UpgradeBondingCurve {
+        mint: Pubkey,
+    },
+}
+
+// Everything `set_params` and `set_oracle` write to `Global`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct Params {
+    pub fee_recipient: Pubkey,
+    pub owner_wallet: Pubkey,
+    // deprecated, see `Global`, never changed
+    pub agent_amount: u64,
+    pub fee_amount: u64,
+    // deprecated, see `Global`, never changed
+    pub create_fee: u64,
+    pub amm_program: Pubkey,
+    pub trade_fee_bps: u64,
+    pub referral_fee_bps: u64,
+    pub graduation_metric: GraduationMetric,
+    pub graduation_threshold: u64,
+    pub min_graduation_threshold: u64,
+    pub max_graduation_threshold: u64,
+    pub params_delay: u64,
+    pub price_oracle: Pubkey,
+    pub usd_graduation_threshold: u64,
+    pub oracle_max_age: u64,
+    pub oracle_max_confidence_bps: u64,
+}
+
+// A partial `set_params` or `set_oracle` change, None keeps the current value. The
+// deprecated params can't be changed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct ParamsUpdate {
+    pub fee_recipient: Option<Pubkey>,
+    pub owner_wallet: Option<Pubkey>,
+    pub fee_amount: Option<u64>,
+    pub amm_program: Option<Pubkey>,
+    pub trade_fee_bps: Option<u64>,
+    pub referral_fee_bps: Option<u64>,
+    pub graduation_metric: Option<GraduationMetric>,
+    pub graduation_threshold: Option<u64>,
+    pub min_graduation_threshold: Option<u64>,
+    pub max_graduation_threshold: Option<u64>,
+    pub params_delay: Option<u64>,
+    pub price_oracle: Option<Pubkey>,
+    pub usd_graduation_threshold: Option<u64>,
+    pub oracle_max_age: Option<u64>,
+    pub oracle_max_confidence_bps: Option<u64>,
+}
+
+impl ParamsUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.fee_recipient.is_none()
+            && self.owner_wallet.is_none()
+            && self.fee_amount.is_none()
+            && self.amm_program.is_none()
+            && self.trade_fee_bps.is_none()
+            && self.referral_fee_bps.is_none()
+            && self.graduation_metric.is_none()
+            && self.graduation_threshold.is_none()
+            && self.min_graduation_threshold.is_none()
+            && self.max_graduation_threshold.is_none()
+            && self.params_delay.is_none()
+            && self.price_oracle.is_none()
+            && self.usd_graduation_threshold.is_none()
+            && self.oracle_max_age.is_none()
+            && self.oracle_max_confidence_bps.is_none()
+    }
+
+    pub fn apply_to(&self, params: &Params) -> Params {
+        Params {
+            fee_recipient: self.fee_recipient.unwrap_or(params.fee_recipient),
+            owner_wallet: self.owner_wallet.unwrap_or(params.owner_wallet),
+            agent_amount: params.agent_amount,
+            fee_amount: self.fee_amount.unwrap_or(params.fee_amount),
+            create_fee: params.create_fee,
+            amm_program: self.amm_program.unwrap_or(params.amm_program),
+            trade_fee_bps: self.trade_fee_bps.unwrap_or(params.trade_fee_bps),
+            referral_fee_bps: self.referral_fee_bps.unwrap_or(params.referral_fee_bps),
+            graduation_metric: self.graduation_metric.unwrap_or(params.graduation_metric),
+            graduation_threshold: self.graduation_threshold.unwrap_or(params.graduation_threshold),
+            min_graduation_threshold: self
+                .min_graduation_threshold
+                .unwrap_or(params.min_graduation_threshold),
+            max_graduation_threshold: self
+                .max_graduation_threshold
+                .unwrap_or(params.max_graduation_threshold),
+            params_delay: self.params_delay.unwrap_or(params.params_delay),
+            price_oracle: self.price_oracle.unwrap_or(params.price_oracle),
+            usd_graduation_threshold: self
+                .usd_graduation_threshold
+                .unwrap_or(params.usd_graduation_threshold),
+            oracle_max_age: self.oracle_max_age.unwrap_or(params.oracle_max_age),
+            oracle_max_confidence_bps: self
+                .oracle_max_confidence_bps
+                .unwrap_or(params.oracle_max_confidence_bps),
+        }
+    }
+}
+
+// Referral rewards of one referrer, held in the shared vault until claimed.
+#[account]
+pub struct Referral {
+    pub referrer: Pubkey,
+    pub unclaimed: u64,
+    pub total_earned: u64,
+}
+
+// USD price of one whole F44 pushed by the authority, price * 10^expo.
+// Stands in for a Pyth price account where there is none, e.g. on localnet.
+#[account]
+pub struct PriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+#[account]
+pub struct LiquidityPosition {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub shares: u64,
+}
+
+// Pricing formula chosen at creation. `token_reserves` on the curve is the x axis.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveKind {
+    // price = initial_price + curve_slope * x
+    Linear {
+        initial_price: u128,
+        curve_slope: u128,
+    },
+    // price = initial_price * e^(growth_rate * x), growth_rate scaled by `PRICE_SCALE`
+    Exponential {
+        initial_price: u128,
+        growth_rate: u128,
+    },
+    // virtual_f44_reserves * virtual_token_reserves = k, with x tokens taken out of the token side
+    ConstantProduct {
+        virtual_f44_reserves: u64,
+        virtual_token_reserves: u64,
+    },
+}
+
+// What a curve's graduation threshold is measured in.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraduationMetric {
+    // `current_mcap`, in quote base units
+    MarketCap,
+    // `f44_reserves`, in quote base units
+    F44Raised,
+    // `token_reserves`, in agent token base units
+    TokensSold,
+}
+
+// Layout of `Global` before the switch to fixed-point math. Only read by `upgrade_global`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LegacyGlobal {
+    pub initialized: bool,
+    pub authority: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub owner_wallet: Pubkey,
+    pub f44_mint: Pubkey,
+    pub f44_vault: Pubkey,
+    pub f44_supply: u64,
+    pub fee_amount: u64,
+    pub agent_amount: u64,
+    pub create_fee: u64,
+}
+
+// Layout of bonding curves created before the switch to fixed-point math.
+// Only read by `upgrade_bonding_curve`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LegacyBondingCurve {
     pub initial_price: f64,
     pub curve_slope: f64,
     pub token_reserves: f64,
@@ -25,4 +420,4 @@ pub struct BondingCurve {
     pub current_price: f64,
     pub current_mcap: f64,
     pub complete: bool,
-}
\ No newline at end of file
+}
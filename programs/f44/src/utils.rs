@@ -0,0 +1,304 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{
+    allocate, assign, create_account, transfer, Allocate, Assign, CreateAccount, Transfer,
+};
+use anchor_spl::token_2022::{
+    spl_token_2022::{
+        extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+        state::Mint as MintState,
+    },
+    Token2022,
+};
+
+use crate::{
+    constants::BPS_DENOMINATOR,
+    error::F44Code,
+    math::{mul_div, to_u64, Rounding},
+    state::{BondingCurve, Global, QuoteConfig, Referral},
+};
+
+fn transfer_fee_config(mint_info: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint_info.owner != Token2022::id() {
+        return Ok(None);
+    }
+    let data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<MintState>::unpack(&data)?;
+    Ok(mint.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+// Fee withheld by a Token-2022 transfer-fee mint when `amount` is sent this epoch.
+// Zero for classic SPL mints and Token-2022 mints without the extension.
+pub fn transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint_info)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(F44Code::MathOverflow)),
+        None => Ok(0),
+    }
+}
+
+// Amount that has to be sent so the receiver is credited at least `net`.
+pub fn amount_with_fee(mint_info: &AccountInfo, net: u64) -> Result<u64> {
+    let fee = match transfer_fee_config(mint_info)? {
+        Some(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, net)
+            .ok_or(F44Code::MathOverflow)?,
+        None => 0,
+    };
+    net.checked_add(fee).ok_or(error!(F44Code::MathOverflow))
+}
+
+// Creates a PDA owned by `owner`. Works even if someone already sent lamports to the
+// address, which would otherwise make `create_account` fail and block the caller.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+
+    if current_lamports == 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+        return create_account(cpi_ctx.with_signer(&[seeds]), rent, space as u64, owner);
+    }
+
+    let top_up = rent.saturating_sub(current_lamports);
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+        );
+        transfer(cpi_ctx, top_up)?;
+    }
+    let cpi_ctx = CpiContext::new(
+        system_program.clone(),
+        Allocate {
+            account_to_allocate: account.clone(),
+        },
+    );
+    allocate(cpi_ctx.with_signer(&[seeds]), space as u64)?;
+    let cpi_ctx = CpiContext::new(
+        system_program.clone(),
+        Assign {
+            account_to_assign: account.clone(),
+        },
+    );
+    assign(cpi_ctx.with_signer(&[seeds]), owner)
+}
+
+// Every F44 in the shared vault belongs to a curve, a built-in pool or the protocol.
+// Tokens sent to the vault directly are credited to the protocol fees, so once checked
+// the vault holds exactly what is accounted for.
+pub fn check_f44_vault(global: &mut Global, vault_balance: u64) -> Result<()> {
+    let accounted = global
+        .f44_supply
+        .checked_add(global.protocol_fees)
+        .ok_or(F44Code::MathOverflow)?;
+    require!(vault_balance >= accounted, F44Code::VaultBalanceMismatch);
+    credit_untracked(&mut global.protocol_fees, vault_balance - accounted);
+    Ok(())
+}
+
+// Books a vault balance nobody accounted for, it can't overflow as it fits the vault.
+fn credit_untracked(protocol_fees: &mut u64, excess: u64) {
+    if excess > 0 {
+        msg!("untracked vault balance credited to protocol fees - amount: {}", excess);
+        *protocol_fees += excess;
+    }
+}
+
+// Curves quoted in F44 are accounted on `Global`, curves quoted in another whitelisted
+// mint on that mint's `QuoteConfig`, which has to be passed exactly for those.
+pub fn check_quote_config(global: &Global, bonding_curve: &BondingCurve, quote_config: Option<&QuoteConfig>) -> Result<()> {
+    require!(
+        (bonding_curve.quote_mint != global.f44_mint) == quote_config.is_some(),
+        F44Code::InvalidQuoteMint
+    );
+    Ok(())
+}
+
+// Quote held for curves in the vault of their quote mint.
+pub fn quote_supply<'a>(global: &'a mut Global, quote_config: Option<&'a mut QuoteConfig>) -> &'a mut u64 {
+    match quote_config {
+        Some(quote_config) => &mut quote_config.supply,
+        None => &mut global.f44_supply,
+    }
+}
+
+// `check_f44_vault` for the vault of any quote mint.
+pub fn check_quote_vault(global: &mut Global, quote_config: Option<&mut QuoteConfig>, vault_balance: u64) -> Result<()> {
+    let Some(quote_config) = quote_config else {
+        return check_f44_vault(global, vault_balance);
+    };
+    let accounted = quote_config
+        .supply
+        .checked_add(quote_config.protocol_fees)
+        .ok_or(F44Code::MathOverflow)?;
+    require!(vault_balance >= accounted, F44Code::VaultBalanceMismatch);
+    credit_untracked(&mut quote_config.protocol_fees, vault_balance - accounted);
+    Ok(())
+}
+
+// Buys, creates and withdraws stop on any pause, global or on the curve.
+pub fn check_not_paused(global: &Global, bonding_curve: Option<&BondingCurve>) -> Result<()> {
+    require!(!global.paused, F44Code::Paused);
+    require!(!bonding_curve.is_some_and(|c| c.paused), F44Code::Paused);
+    Ok(())
+}
+
+// Sells are the safe exit: they only stop when a pause is not sell-only.
+pub fn check_can_sell(global: &Global, bonding_curve: &BondingCurve) -> Result<()> {
+    require!(!global.paused || global.sell_only, F44Code::Paused);
+    require!(!bonding_curve.paused || bonding_curve.sell_only, F44Code::Paused);
+    Ok(())
+}
+
+// A SOL vault holds its curve's reserves and unclaimed creator and protocol fees on top of
+// the rent that keeps it alive. Lamports sent to it directly are credited to the protocol fees.
+pub fn check_sol_vault(bonding_curve: &mut BondingCurve, vault_lamports: u64) -> Result<()> {
+    let accounted = Rent::get()?
+        .minimum_balance(0)
+        .checked_add(bonding_curve.f44_reserves)
+        .and_then(|v| v.checked_add(bonding_curve.creator_fees))
+        .and_then(|v| v.checked_add(bonding_curve.protocol_fees))
+        .ok_or(F44Code::MathOverflow)?;
+    require!(vault_lamports >= accounted, F44Code::VaultBalanceMismatch);
+    credit_untracked(&mut bonding_curve.protocol_fees, vault_lamports - accounted);
+    Ok(())
+}
+
+// Moves lamports with a system transfer, `signer_seeds` is empty unless `from` is a PDA.
+pub fn transfer_lamports<'info>(
+    system_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new_with_signer(
+        system_program.clone(),
+        Transfer {
+            from: from.clone(),
+            to: to.clone(),
+        },
+        signer_seeds,
+    );
+    transfer(cpi_ctx, amount)
+}
+
+// The referrer's share of a trade's `fee`, rounded down.
+pub fn referral_share(global: &Global, fee: u64) -> Result<u64> {
+    to_u64(mul_div(
+        fee as u128,
+        global.referral_fee_bps as u128,
+        BPS_DENOMINATOR as u128,
+        Rounding::Down,
+    )?)
+}
+
+// Credits `amount` to the referrer of a trade. The referrer and its referral account
+// come together, and nobody refers themselves.
+pub fn credit_referral(
+    user: Pubkey,
+    referrer: Option<Pubkey>,
+    referral: Option<&mut Referral>,
+    amount: u64,
+) -> Result<()> {
+    let (referrer, referral) = match (referrer, referral) {
+        (None, None) => return Ok(()),
+        (Some(referrer), Some(referral)) => (referrer, referral),
+        _ => return err!(F44Code::InvalidReferrer),
+    };
+    require!(referrer != user, F44Code::InvalidReferrer);
+
+    referral.referrer = referrer;
+    referral.unclaimed = referral
+        .unclaimed
+        .checked_add(amount)
+        .ok_or(F44Code::MathOverflow)?;
+    referral.total_earned = referral
+        .total_earned
+        .checked_add(amount)
+        .ok_or(F44Code::MathOverflow)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `Global` is all integers, keys, flags and field-less enums, so zeroed bytes decode to a
+    // blank one
+    fn global() -> Global {
+        Global::deserialize(&mut &[0_u8; 1024][..]).unwrap()
+    }
+
+    fn referral() -> Referral {
+        Referral {
+            referrer: Pubkey::default(),
+            unclaimed: 0,
+            total_earned: 0,
+        }
+    }
+
+    #[test]
+    fn referral_share_is_a_rounded_down_part_of_the_fee() {
+        let mut global = global();
+        global.referral_fee_bps = 2_000;
+        assert_eq!(referral_share(&global, 1_000).unwrap(), 200);
+        assert_eq!(referral_share(&global, 999).unwrap(), 199);
+        assert_eq!(referral_share(&global, 4).unwrap(), 0);
+        assert_eq!(referral_share(&global, u64::MAX).unwrap(), u64::MAX / 5);
+
+        global.referral_fee_bps = BPS_DENOMINATOR;
+        assert_eq!(referral_share(&global, 1_234).unwrap(), 1_234);
+        global.referral_fee_bps = 0;
+        assert_eq!(referral_share(&global, 1_234).unwrap(), 0);
+    }
+
+    #[test]
+    fn referrals_need_both_accounts_and_another_user() {
+        let user = Pubkey::new_unique();
+        let referrer = Pubkey::new_unique();
+
+        credit_referral(user, None, None, 100).unwrap();
+        assert!(credit_referral(user, Some(referrer), None, 100).is_err());
+        assert!(credit_referral(user, None, Some(&mut referral()), 100).is_err());
+        assert!(credit_referral(user, Some(user), Some(&mut referral()), 100).is_err());
+
+        let mut referral = referral();
+        credit_referral(user, Some(referrer), Some(&mut referral), 100).unwrap();
+        credit_referral(user, Some(referrer), Some(&mut referral), 50).unwrap();
+        assert_eq!(referral.referrer, referrer);
+        assert_eq!(referral.unclaimed, 150);
+        assert_eq!(referral.total_earned, 150);
+    }
+
+    #[test]
+    fn untracked_vault_balance_goes_to_protocol_fees() {
+        let mut global = global();
+        global.f44_supply = 1_000;
+        global.protocol_fees = 10;
+
+        check_f44_vault(&mut global, 1_010).unwrap();
+        assert_eq!(global.protocol_fees, 10);
+        check_f44_vault(&mut global, 1_500).unwrap();
+        assert_eq!(global.protocol_fees, 500);
+        assert_eq!(global.f44_supply, 1_000);
+        // less than accounted is never fine
+        assert!(check_f44_vault(&mut global, 1_499).is_err());
+    }
+}
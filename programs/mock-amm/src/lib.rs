@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{
+        mint_to, transfer_checked, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
+};
+use std::mem::size_of;
+
+declare_id!("BdxY3a8W3DTBGLULdAvd9nHfyNif8RQLX73BRmeCrxGF");
+
+pub const POOL_SEED: &[u8] = b"pool";
+pub const LP_MINT_SEED: &[u8] = b"lp_mint";
+pub const POOL_VAULT_SEED: &[u8] = b"pool_vault";
+
+pub const LP_DECIMALS: u8 = 9;
+
+// Stand-in for a real DEX so `f44::migrate` can be exercised on a local validator or in
+// solana-program-test. Only pool creation is implemented: the creator deposits both sides
+// and receives sqrt(amount_a * amount_b) LP tokens.
+#[program]
+pub mod mock_amm {
+    use super::*;
+
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        amount_a: u64,
+        amount_b: u64,
+    ) -> Result<()> {
+        let accts = ctx.accounts;
+
+        require!(amount_a > 0 && amount_b > 0, MockAmmError::ZeroAmount);
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program_a.to_account_info(),
+            TransferChecked {
+                from: accts.creator_token_a.to_account_info(),
+                mint: accts.mint_a.to_account_info(),
+                to: accts.vault_a.to_account_info(),
+                authority: accts.creator.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount_a, accts.mint_a.decimals)?;
+
+        let cpi_ctx = CpiContext::new(
+            accts.token_program_b.to_account_info(),
+            TransferChecked {
+                from: accts.creator_token_b.to_account_info(),
+                mint: accts.mint_b.to_account_info(),
+                to: accts.vault_b.to_account_info(),
+                authority: accts.creator.to_account_info(),
+            },
+        );
+        transfer_checked(cpi_ctx, amount_b, accts.mint_b.decimals)?;
+
+        let liquidity = isqrt(amount_a as u128 * amount_b as u128) as u64;
+        require!(liquidity > 0, MockAmmError::ZeroAmount);
+
+        let mint_a = accts.mint_a.key();
+        let mint_b = accts.mint_b.key();
+        let pool_seeds = &[
+            POOL_SEED,
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            &[ctx.bumps.pool],
+        ];
+        let signer = &[&pool_seeds[..]];
+
+        let cpi_ctx = CpiContext::new(
+            accts.lp_token_program.to_account_info(),
+            MintTo {
+                mint: accts.lp_mint.to_account_info(),
+                to: accts.creator_lp.to_account_info(),
+                authority: accts.pool.to_account_info(),
+            },
+        );
+        mint_to(cpi_ctx.with_signer(signer), liquidity)?;
+
+        let pool = &mut accts.pool;
+        pool.mint_a = mint_a;
+        pool.mint_b = mint_b;
+        pool.vault_a = accts.vault_a.key();
+        pool.vault_b = accts.vault_b.key();
+        pool.lp_mint = accts.lp_mint.key();
+        pool.lp_supply = liquidity;
+        pool.bump = ctx.bumps.pool;
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub creator: Signer<'info>,
+
+    #[account(mint::token_program = token_program_a)]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = token_program_b)]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POOL_SEED, mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        space = 8 + size_of::<Pool>()
+    )]
+    pub pool: Box<Account<'info, Pool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [LP_MINT_SEED, pool.key().as_ref()],
+        bump,
+        mint::decimals = LP_DECIMALS,
+        mint::authority = pool,
+        mint::token_program = lp_token_program,
+    )]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POOL_VAULT_SEED, pool.key().as_ref(), mint_a.key().as_ref()],
+        bump,
+        token::mint = mint_a,
+        token::authority = pool,
+        token::token_program = token_program_a,
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [POOL_VAULT_SEED, pool.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        token::mint = mint_b,
+        token::authority = pool,
+        token::token_program = token_program_b,
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_a,
+        token::authority = creator,
+        token::token_program = token_program_a,
+    )]
+    pub creator_token_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::authority = creator,
+        token::token_program = token_program_b,
+    )]
+    pub creator_token_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = lp_token_program,
+    )]
+    pub creator_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program_a: Interface<'info, TokenInterface>,
+    pub token_program_b: Interface<'info, TokenInterface>,
+    pub lp_token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[account]
+pub struct Pool {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub lp_supply: u64,
+    pub bump: u8,
+}
+
+#[error_code]
+pub enum MockAmmError {
+    #[msg("Both deposit amounts must be bigger than 0.")]
+    ZeroAmount,
+}
+
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
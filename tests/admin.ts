@@ -0,0 +1,331 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  SystemProgram,
+  Keypair,
+  PublicKey,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  owner,
+  user,
+  buyer,
+  tradeFeeBps,
+  global,
+  agentMint,
+  bondingCurve,
+  paramsUpdate,
+  queueParams,
+} from "./helpers";
+
+describe("admin", () => {
+  it("The authority changes hands only once the proposed key accepts", async() => {
+    await program.rpc.proposeAuthority(buyer.publicKey, {
+      accounts: { global, authority: owner.publicKey },
+      signers: [owner]
+    });
+    assert.ok((await program.account.global.fetch(global)).pendingAuthority.equals(buyer.publicKey));
+
+    try {
+      await program.rpc.acceptAuthority({
+        accounts: { global, newAuthority: user.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+        signers: [user]
+      });
+      assert.fail("only the proposed key can accept");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotAuthorized");
+    }
+
+    await program.rpc.acceptAuthority({
+      accounts: { global, newAuthority: buyer.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+      signers: [buyer]
+    });
+    let globalData = await program.account.global.fetch(global);
+    assert.ok(globalData.authority.equals(buyer.publicKey));
+    assert.ok(globalData.pendingAuthority.equals(PublicKey.default));
+
+    // hand control back so the remaining tests run as the owner, cancelling a stray proposal first
+    await program.rpc.proposeAuthority(user.publicKey, {
+      accounts: { global, authority: buyer.publicKey },
+      signers: [buyer]
+    });
+    await program.rpc.cancelAuthorityTransfer({
+      accounts: { global, authority: buyer.publicKey },
+      signers: [buyer]
+    });
+    assert.ok((await program.account.global.fetch(global)).pendingAuthority.equals(PublicKey.default));
+    await program.rpc.proposeAuthority(owner.publicKey, {
+      accounts: { global, authority: buyer.publicKey },
+      signers: [buyer]
+    });
+    await program.rpc.acceptAuthority({
+      accounts: { global, newAuthority: owner.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+      signers: [owner]
+    });
+    globalData = await program.account.global.fetch(global);
+    assert.ok(globalData.authority.equals(owner.publicKey));
+  });
+  it("Once the admin multisig is the authority, changes need its threshold of approvals", async() => {
+    const [adminSet] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ADMIN-SET-SEED")],
+      program.programId
+    );
+    const actionAddress = (nonce: anchor.BN) => PublicKey.findProgramAddressSync(
+      [Buffer.from("ADMIN-ACTION-SEED"), nonce.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    // proposes `kind` as `proposer` and returns the action account
+    const propose = async (kind, proposer: Keypair) => {
+      const action = actionAddress((await program.account.adminSet.fetch(adminSet)).actionCount);
+      await program.rpc.proposeAction(kind, {
+        accounts: {
+          adminSet,
+          action,
+          member: proposer.publicKey,
+          systemProgram: SystemProgram.programId,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [proposer]
+      });
+      return action;
+    };
+    const approve = (action: PublicKey, member: Keypair) => program.rpc.approveAction({
+      accounts: { adminSet, action, member: member.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+      signers: [member]
+    });
+    const execute = (action: PublicKey, member: Keypair) => program.rpc.executeAction({
+      accounts: { global, adminSet, action, member: member.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+      signers: [member]
+    });
+
+    // 2-of-3 multisig, proposed as authority by the owner and accepted by the members
+    await program.rpc.createAdminSet([owner.publicKey, user.publicKey, buyer.publicKey], 2, {
+      accounts: {
+        global,
+        adminSet,
+        authority: owner.publicKey,
+        systemProgram: SystemProgram.programId
+      },
+      signers: [owner]
+    });
+    await program.rpc.proposeAuthority(adminSet, {
+      accounts: { global, authority: owner.publicKey },
+      signers: [owner]
+    });
+    const accept = await propose({ acceptAuthority: {} }, owner);
+    try {
+      await execute(accept, owner);
+      assert.fail("one approval is below the threshold");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotEnoughApprovals");
+    }
+    try {
+      await approve(accept, owner);
+      assert.fail("a member approves only once");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "ActionAlreadyApproved");
+    }
+    await approve(accept, user);
+    await execute(accept, buyer);
+    assert.ok((await program.account.global.fetch(global)).authority.equals(adminSet));
+
+    // the former authority can no longer change params alone
+    const globalData = await program.account.global.fetch(global);
+    try {
+      await queueParams({ tradeFeeBps: new anchor.BN(tradeFeeBps * 2) }, owner);
+      assert.fail("set_params should need the multisig");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotAuthorized");
+    }
+
+    // only the fee changes, every other field keeps its current value
+    const setParams = await propose({
+      setParams: { update: paramsUpdate({ tradeFeeBps: new anchor.BN(tradeFeeBps * 2) }) }
+    }, user);
+    await approve(setParams, buyer);
+    await execute(setParams, user);
+    // queued behind the timelock like any params change, the delay is 0 here
+    await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+    assert.equal((await program.account.global.fetch(global)).tradeFeeBps.toNumber(), tradeFeeBps * 2);
+    try {
+      await execute(setParams, user);
+      assert.fail("an action runs once");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "ActionAlreadyExecuted");
+    }
+
+    // restore the fee and hand control back to the owner for the remaining tests
+    const restore = await propose({
+      setParams: { update: paramsUpdate({ tradeFeeBps: globalData.tradeFeeBps }) }
+    }, owner);
+    await approve(restore, user);
+    await execute(restore, owner);
+    await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+
+    // instructions with accounts of their own take the approved action in place of the authority
+    const pauseCurve = (paused: boolean, reason: string, member: Keypair, adminAction: PublicKey | null) =>
+      program.rpc.setCurvePause(paused, false, reason, {
+        accounts: {
+          global,
+          mint: agentMint,
+          bondingCurve,
+          authority: member.publicKey,
+          adminSet: adminAction ? adminSet : null,
+          adminAction,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [member]
+      });
+    try {
+      await pauseCurve(true, "review", owner, null);
+      assert.fail("set_curve_pause should need the multisig");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotAuthorized");
+    }
+    const pause = await propose({
+      setCurvePause: { mint: agentMint, paused: true, sellOnly: false, reason: "review" }
+    }, user);
+    await approve(pause, owner);
+    try {
+      await execute(pause, owner);
+      assert.fail("execute_action lacks the curve account");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "WrongAdminAction");
+    }
+    try {
+      await pauseCurve(true, "something else", buyer, pause);
+      assert.fail("the call has to match the approved action");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "WrongAdminAction");
+    }
+    await pauseCurve(true, "review", buyer, pause);
+    assert.ok((await program.account.bondingCurve.fetch(bondingCurve)).paused);
+    const unpause = await propose({
+      setCurvePause: { mint: agentMint, paused: false, sellOnly: false, reason: "done" }
+    }, user);
+    await approve(unpause, buyer);
+    await pauseCurve(false, "done", user, unpause);
+    assert.ok(!(await program.account.bondingCurve.fetch(bondingCurve)).paused);
+
+    // raising the threshold voids the actions proposed under the old one
+    const members = [owner.publicKey, user.publicKey, buyer.publicKey];
+    const stale = await propose({ cancelPendingParams: {} }, owner);
+    const rotate = await propose({
+      updateAdminSet: {
+        members: [...members, ...Array(10 - members.length).fill(PublicKey.default)],
+        memberCount: members.length,
+        threshold: 3,
+      }
+    }, owner);
+    await approve(rotate, user);
+    await execute(rotate, owner);
+    assert.equal((await program.account.adminSet.fetch(adminSet)).threshold, 3);
+    try {
+      await approve(stale, user);
+      assert.fail("the action predates the rotation");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "StaleAdminAction");
+    }
+
+    const handBack = await propose({ proposeAuthority: { newAuthority: owner.publicKey } }, owner);
+    await approve(handBack, buyer);
+    await approve(handBack, user);
+    await execute(handBack, owner);
+    await program.rpc.acceptAuthority({
+      accounts: { global, newAuthority: owner.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+      signers: [owner]
+    });
+    const restored = await program.account.global.fetch(global);
+    assert.ok(restored.authority.equals(owner.publicKey));
+    assert.equal(restored.tradeFeeBps.toNumber(), tradeFeeBps);
+  });
+  it("Params changes wait out the timelock and can be cancelled", async() => {
+    const apply = () => program.rpc.applyPendingParams({
+      accounts: { global, clock: SYSVAR_CLOCK_PUBKEY }
+    });
+    const queue = (changes: { feeAmount?: anchor.BN, paramsDelay?: anchor.BN }) =>
+      queueParams(changes, owner);
+    const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+    const delay = 2;
+
+    // setting the delay is itself queued behind the old, empty one
+    await queue({ paramsDelay: new anchor.BN(delay) });
+    await apply();
+    const before = await program.account.global.fetch(global);
+    assert.equal(before.paramsDelay.toNumber(), delay);
+
+    await queue({ feeAmount: before.feeAmount.addn(1) });
+    let queued = await program.account.global.fetch(global);
+    assert.equal(queued.feeAmount.toString(), before.feeAmount.toString());
+    assert.equal(queued.pendingParams.feeAmount.toString(), before.feeAmount.addn(1).toString());
+    try {
+      await apply();
+      assert.fail("the change is still timelocked");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "ParamsTimelocked");
+    }
+
+    await program.rpc.cancelPendingParams({
+      accounts: { global, authority: owner.publicKey },
+      signers: [owner]
+    });
+    try {
+      await apply();
+      assert.fail("nothing is queued after a cancel");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NoPendingParams");
+    }
+
+    // once the delay passes anyone may apply, then the delay is turned off again
+    await queue({ feeAmount: before.feeAmount.addn(1) });
+    await sleep((delay + 1) * 1000);
+    await apply();
+    assert.equal(
+      (await program.account.global.fetch(global)).feeAmount.toString(),
+      before.feeAmount.addn(1).toString()
+    );
+    await queue({ feeAmount: before.feeAmount, paramsDelay: new anchor.BN(0) });
+    await sleep((delay + 1) * 1000);
+    await apply();
+    queued = await program.account.global.fetch(global);
+    assert.equal(queued.feeAmount.toString(), before.feeAmount.toString());
+    assert.equal(queued.paramsDelay.toNumber(), 0);
+  });
+  it("set_params rejects nonsensical values and only changes the given fields", async() => {
+    const expectError = async (changes: Record<string, any>, code: string) => {
+      try {
+        await queueParams(changes, owner);
+        assert.fail(`expected ${code}`);
+      } catch (error) {
+        assert.equal(error.error?.errorCode?.code, code);
+      }
+    };
+    await expectError({}, "InvalidParams");
+    await expectError({ feeRecipient: PublicKey.default }, "UnValidFeeRecipient");
+    await expectError({ ownerWallet: PublicKey.default }, "InvalidParams");
+    // the create fee is charged in whole F44, so it is capped to stay in range
+    await expectError({ feeAmount: new anchor.BN(1_000_000_001) }, "InvalidParams");
+    await expectError({ ammProgram: program.programId }, "InvalidAmmProgram");
+    // referrers are paid out of the trading fee
+    await expectError({ tradeFeeBps: new anchor.BN(0) }, "InvalidFeeBps");
+    await expectError({ minGraduationThreshold: new anchor.BN(0) }, "InvalidGraduationThreshold");
+    // a curve cannot sell more than the agent token supply
+    await expectError(
+      { graduationMetric: { tokensSold: {} }, maxGraduationThreshold: new anchor.BN(10 ** 15 + 1) },
+      "InvalidGraduationThreshold"
+    );
+
+    const before = await program.account.global.fetch(global);
+    await queueParams({ feeAmount: before.feeAmount.addn(1) }, owner);
+    await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+    const after = await program.account.global.fetch(global);
+    assert.equal(after.feeAmount.toString(), before.feeAmount.addn(1).toString());
+    assert.ok(after.feeRecipient.equals(before.feeRecipient));
+    assert.equal(after.tradeFeeBps.toNumber(), before.tradeFeeBps.toNumber());
+    assert.equal(after.graduationThreshold.toString(), before.graduationThreshold.toString());
+
+    await queueParams({ feeAmount: before.feeAmount }, owner);
+    await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+  });
+});
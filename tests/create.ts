@@ -0,0 +1,205 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getTokenMetadata,
+  getAssociatedTokenAddress,
+  getAccount,
+  getMint,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
+import { fetchMetadata } from "@metaplex-foundation/mpl-token-metadata";
+import { publicKey as umiPublicKey } from "@metaplex-foundation/umi";
+import {
+  program,
+  connection,
+  user,
+  f44Mint,
+  AGENT_TOKEN_DECIMALS,
+  AGENT_TOKEN_SUPPLY,
+  TOKEN_METADATA_PROGRAM_ID,
+  agentMetadata,
+  creatorFeeBps,
+  minGraduationThreshold,
+  global,
+  f44Vault,
+  curveAddresses,
+  mintNonce,
+  agentMint,
+  bondingCurve,
+  associatedBondingCurve,
+  metadata,
+} from "./helpers";
+
+describe("create", () => {
+  it("Create the pool", async() => {
+    try {
+      // 0.01 F44 per token, rising 0.00001 F44 per token sold (both mints use 6 decimals)
+      const curve = {
+        linear: {
+          initialPrice: new anchor.BN("10000000000000000"),
+          curveSlope: new anchor.BN("10000000")
+        }
+      };
+      const associatedUserF44Account = await getAssociatedTokenAddress(
+        f44Mint,
+        user.publicKey
+      );
+
+      const tx = await program.rpc.create(
+        curve,
+        mintNonce,
+        agentMetadata.name,
+        agentMetadata.symbol,
+        agentMetadata.uri,
+        new anchor.BN(creatorFeeBps),
+        null, {
+          accounts: {
+            user: user.publicKey,
+            global,
+            mint: agentMint,
+            bondingCurve,
+            associatedBondingCurve,
+            metadata,
+            f44Mint,
+            f44Vault,
+            quoteConfig: null,
+            associatedUserF44Account,
+            quoteMint: f44Mint,
+            solVault: null,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            f44TokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            rent: SYSVAR_RENT_PUBKEY
+          },
+          signers: [user]
+        }
+      );
+      console.log("Create Pool tx hash is ", tx);
+
+      const mintData = await getMint(connection, agentMint);
+      assert.equal(mintData.supply.toString(), AGENT_TOKEN_SUPPLY.toString());
+      assert.equal(mintData.mintAuthority, null);
+      assert.equal(mintData.freezeAuthority, null);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("The agent token is a fungible with immutable Metaplex metadata and its supply in the curve vault", async() => {
+    // what tests/mint.ts used to mint by hand before `create` minted the agent token itself
+    const mintData = await getMint(connection, agentMint);
+    assert.equal(mintData.decimals, AGENT_TOKEN_DECIMALS);
+    assert.equal(
+      (await getAccount(connection, associatedBondingCurve)).amount.toString(),
+      AGENT_TOKEN_SUPPLY.toString()
+    );
+
+    const umi = createUmi(connection.rpcEndpoint);
+    const onChain = await fetchMetadata(umi, umiPublicKey(metadata.toBase58()));
+    // Metaplex pads the strings with zero bytes
+    const trim = (value: string) => value.replace(/\0/g, "");
+    assert.equal(onChain.mint.toString(), agentMint.toBase58());
+    assert.equal(trim(onChain.name), agentMetadata.name);
+    assert.equal(trim(onChain.symbol), agentMetadata.symbol);
+    assert.equal(trim(onChain.uri), agentMetadata.uri);
+    assert.equal(onChain.sellerFeeBasisPoints, 0);
+    assert.equal(onChain.isMutable, false);
+  });
+  it("Create a Token-2022 agent pool with on-mint metadata", async() => {
+    const nonce = new anchor.BN(Date.now() + 1);
+    const { mint: mint2022, curve: curve2022, vault: vault2022 } = curveAddresses(nonce);
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      user.publicKey
+    );
+
+    await program.rpc.create(
+      { linear: { initialPrice: new anchor.BN("10000000000000000"), curveSlope: new anchor.BN("10000000") } },
+      nonce,
+      agentMetadata.name,
+      agentMetadata.symbol,
+      agentMetadata.uri,
+      new anchor.BN(creatorFeeBps),
+      null, {
+        accounts: {
+          user: user.publicKey,
+          global,
+          mint: mint2022,
+          bondingCurve: curve2022,
+          associatedBondingCurve: vault2022,
+          metadata: null,
+          f44Mint,
+          f44Vault,
+          quoteConfig: null,
+          associatedUserF44Account,
+          quoteMint: f44Mint,
+          solVault: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: null,
+          rent: SYSVAR_RENT_PUBKEY
+        },
+        signers: [user]
+      }
+    );
+
+    const mintData = await getMint(connection, mint2022, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(mintData.supply.toString(), AGENT_TOKEN_SUPPLY.toString());
+    assert.equal(mintData.mintAuthority, null);
+    const tokenMetadata = await getTokenMetadata(connection, mint2022);
+    assert.equal(tokenMetadata.name, agentMetadata.name);
+    assert.equal(tokenMetadata.symbol, agentMetadata.symbol);
+    assert.equal(tokenMetadata.uri, agentMetadata.uri);
+    assert.equal(tokenMetadata.updateAuthority, undefined);
+  });
+  it("A graduation threshold outside the admin bounds is rejected", async() => {
+    const nonce = new anchor.BN(Date.now() + 3);
+    const { mint, curve, vault } = curveAddresses(nonce);
+    try {
+      await program.rpc.create(
+        { linear: { initialPrice: new anchor.BN("1000000000000"), curveSlope: new anchor.BN("1000") } },
+        nonce,
+        agentMetadata.name,
+        agentMetadata.symbol,
+        agentMetadata.uri,
+        new anchor.BN(creatorFeeBps),
+        minGraduationThreshold.subn(1), {
+          accounts: {
+            user: user.publicKey,
+            global,
+            mint,
+            bondingCurve: curve,
+            associatedBondingCurve: vault,
+            metadata: null,
+            f44Mint,
+            f44Vault,
+            quoteConfig: null,
+            associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, user.publicKey),
+            quoteMint: f44Mint,
+            solVault: null,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            f44TokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            tokenMetadataProgram: null,
+            rent: SYSVAR_RENT_PUBKEY
+          },
+          signers: [user]
+        }
+      );
+      assert.fail("create should reject a threshold below the minimum");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidGraduationThreshold");
+    }
+  });
+});
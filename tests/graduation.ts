@@ -0,0 +1,444 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+  getAccount,
+  getMint,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  PublicKey,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  connection,
+  owner,
+  buyer,
+  f44Mint,
+  MOCK_AMM_PROGRAM_ID,
+  global,
+  f44Vault,
+  agentMint,
+  bondingCurve,
+  associatedBondingCurve,
+  pool,
+  queueParams,
+  launchCurve,
+  completeCurve,
+} from "./helpers";
+
+describe("graduation", () => {
+  it("Migrate is rejected until the curve completes", async() => {
+    const [ammPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), f44Mint.toBuffer(), agentMint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), ammPool.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [poolF44Vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_vault"), ammPool.toBuffer(), f44Mint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [poolTokenVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_vault"), ammPool.toBuffer(), agentMint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+
+    try {
+      await program.rpc.migrate({
+        accounts: {
+          user: buyer.publicKey,
+          global,
+          mint: agentMint,
+          bondingCurve,
+          associatedBondingCurve,
+          f44Mint,
+          f44Vault,
+          associatedBondingCurveF44: getAssociatedTokenAddressSync(f44Mint, bondingCurve, true),
+          pool: ammPool,
+          lpMint,
+          poolF44Vault,
+          poolTokenVault,
+          associatedBondingCurveLp: getAssociatedTokenAddressSync(lpMint, bondingCurve, true),
+          ammProgram: MOCK_AMM_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          lpTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [buyer]
+      });
+      assert.fail("migrate should fail before the curve completes");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "BondingCurveNotComplete");
+    }
+  });
+  it("A completed curve migrates onto the AMM and its LP tokens are burned", async() => {
+    const launched = await launchCurve(new anchor.BN(Date.now() + 7));
+    const { mint, curve, vault } = launched;
+    await completeCurve(launched);
+
+    const [ammPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool"), f44Mint.toBuffer(), mint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [lpMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("lp_mint"), ammPool.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [poolF44Vault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_vault"), ammPool.toBuffer(), f44Mint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const [poolTokenVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("pool_vault"), ammPool.toBuffer(), mint.toBuffer()],
+      MOCK_AMM_PROGRAM_ID
+    );
+    const associatedBondingCurveLp = getAssociatedTokenAddressSync(lpMint, curve, true);
+
+    const before = await program.account.bondingCurve.fetch(curve);
+    const globalBefore = await program.account.global.fetch(global);
+    const tokensLeft = (await getAccount(connection, vault, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+    assert.ok(before.complete);
+
+    // the owner cannot drain a graduated F44 curve ahead of its migration
+    try {
+      await program.rpc.withdraw({
+        accounts: {
+          global,
+          mint,
+          bondingCurve: curve,
+          associatedBondingCurve: vault,
+          associatedUser: getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          f44Mint,
+          f44Vault,
+          quoteConfig: null,
+          associatedUserF44Account: getAssociatedTokenAddressSync(f44Mint, owner.publicKey),
+          solVault: null,
+          ownerWallet: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+        },
+        signers: [owner]
+      });
+      assert.fail("an F44 curve is not withdrawn before it migrates");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotWithdrawable");
+    }
+
+    await program.rpc.migrate({
+      accounts: {
+        user: buyer.publicKey,
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedBondingCurve: vault,
+        f44Mint,
+        f44Vault,
+        associatedBondingCurveF44: getAssociatedTokenAddressSync(f44Mint, curve, true),
+        pool: ammPool,
+        lpMint,
+        poolF44Vault,
+        poolTokenVault,
+        associatedBondingCurveLp,
+        ammProgram: MOCK_AMM_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        lpTokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+
+    // both sides of the curve seed the pool
+    const after = await program.account.bondingCurve.fetch(curve);
+    assert.ok(after.migrated);
+    assert.equal(after.f44Reserves.toNumber(), 0);
+    assert.equal((await getAccount(connection, poolF44Vault)).amount.toString(), before.f44Reserves.toString());
+    assert.equal(
+      (await getAccount(connection, poolTokenVault, undefined, TOKEN_2022_PROGRAM_ID)).amount.toString(),
+      tokensLeft.toString()
+    );
+    assert.equal((await getAccount(connection, vault, undefined, TOKEN_2022_PROGRAM_ID)).amount.toString(), "0");
+    assert.equal(
+      (await program.account.global.fetch(global)).f44Supply.toString(),
+      globalBefore.f44Supply.sub(before.f44Reserves).toString()
+    );
+
+    // every LP token went to the curve and was burned, nobody can pull the liquidity
+    assert.equal((await getAccount(connection, associatedBondingCurveLp)).amount.toString(), "0");
+    assert.equal((await getMint(connection, lpMint)).supply.toString(), "0");
+
+    try {
+      await program.rpc.migrate({
+        accounts: {
+          user: buyer.publicKey,
+          global,
+          mint,
+          bondingCurve: curve,
+          associatedBondingCurve: vault,
+          f44Mint,
+          f44Vault,
+          associatedBondingCurveF44: getAssociatedTokenAddressSync(f44Mint, curve, true),
+          pool: ammPool,
+          lpMint,
+          poolF44Vault,
+          poolTokenVault,
+          associatedBondingCurveLp,
+          ammProgram: MOCK_AMM_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          lpTokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [buyer]
+      });
+      assert.fail("a curve migrates once");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "AlreadyMigrated");
+    }
+  });
+  it("Swapping on the built-in pool is rejected before the curve graduates", async() => {
+    try {
+      await program.rpc.swap(new anchor.BN(1_000_000), new anchor.BN(0), true, {
+        accounts: {
+          global,
+          mint: agentMint,
+          bondingCurve,
+          pool,
+          associatedBondingCurve,
+          associatedUser: await getAssociatedTokenAddress(agentMint, buyer.publicKey),
+          f44Mint,
+          f44Vault,
+          associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, buyer.publicKey),
+          user: buyer.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [buyer]
+      });
+      assert.fail("swap should fail while the pool does not exist");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "AccountNotInitialized");
+    }
+  });
+  it("A graduated curve trades and takes liquidity on the built-in pool at x*y=k", async() => {
+    const apply = () => program.rpc.applyPendingParams({
+      accounts: { global, clock: SYSVAR_CLOCK_PUBKEY }
+    });
+    // without an external AMM the curve graduates straight into the built-in pool
+    const { ammProgram } = await program.account.global.fetch(global);
+    await queueParams({ ammProgram: PublicKey.default }, owner);
+    await apply();
+
+    const launched = await launchCurve(new anchor.BN(Date.now() + 8));
+    const { mint, curve, vault, curvePool } = launched;
+    const associatedUser = await completeCurve(launched);
+    const curveData = await program.account.bondingCurve.fetch(curve);
+    assert.ok(curveData.complete && curveData.migrated);
+    assert.equal(curveData.f44Reserves.toNumber(), 0);
+
+    let poolData = await program.account.pool.fetch(curvePool);
+    assert.ok(poolData.quoteMint.equals(f44Mint));
+    // seeded with what the vault holds
+    assert.equal(
+      poolData.tokenReserves.toString(),
+      (await getAccount(connection, vault, undefined, TOKEN_2022_PROGRAM_ID)).amount.toString()
+    );
+
+    const k = (p: typeof poolData) => BigInt(p.f44Reserves.toString()) * BigInt(p.tokenReserves.toString());
+    const [position] = PublicKey.findProgramAddressSync(
+      [Buffer.from("POSITION-SEED"), curvePool.toBuffer(), buyer.publicKey.toBuffer()],
+      program.programId
+    );
+    const poolAccounts = (quoteMint: PublicKey, quoteVault: PublicKey) => ({
+      global,
+      mint,
+      bondingCurve: curve,
+      pool: curvePool,
+      associatedBondingCurve: vault,
+      associatedUser,
+      f44Mint: quoteMint,
+      f44Vault: quoteVault,
+      associatedUserF44Account: getAssociatedTokenAddressSync(quoteMint, buyer.publicKey),
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    });
+
+    // F44 in, agent tokens out
+    let before = poolData;
+    const f44In = new anchor.BN(10 ** 6);
+    await program.rpc.swap(f44In, new anchor.BN(0), true, {
+      accounts: poolAccounts(f44Mint, f44Vault),
+      signers: [buyer]
+    });
+    poolData = await program.account.pool.fetch(curvePool);
+    assert.equal(poolData.f44Reserves.toString(), before.f44Reserves.add(f44In).toString());
+    assert.ok(poolData.tokenReserves.lt(before.tokenReserves));
+    // the pool fee stays in the reserves, so k only grows
+    assert.ok(k(poolData) >= k(before));
+
+    // a sell-only pause stops pool buys and deposits but not sells
+    const setGlobalPause = (paused: boolean, sellOnly: boolean) =>
+      program.rpc.setGlobalPause(paused, sellOnly, "pool under review", {
+        accounts: { global, authority: owner.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+        signers: [owner]
+      });
+    await setGlobalPause(true, true);
+    try {
+      await program.rpc.swap(f44In, new anchor.BN(0), true, {
+        accounts: poolAccounts(f44Mint, f44Vault),
+        signers: [buyer]
+      });
+      assert.fail("the pause should stop pool buys");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "Paused");
+    }
+    try {
+      await program.rpc.addLiquidity(new anchor.BN(1), new anchor.BN(10 ** 12), new anchor.BN("1000000000000000"), {
+        accounts: { ...poolAccounts(f44Mint, f44Vault), position },
+        signers: [buyer]
+      });
+      assert.fail("the pause should stop deposits");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "Paused");
+    }
+
+    // agent tokens in, F44 out
+    before = poolData;
+    const tokensIn = new anchor.BN(10 ** 11);
+    await program.rpc.swap(tokensIn, new anchor.BN(0), false, {
+      accounts: poolAccounts(f44Mint, f44Vault),
+      signers: [buyer]
+    });
+    poolData = await program.account.pool.fetch(curvePool);
+    assert.equal(poolData.tokenReserves.toString(), before.tokenReserves.add(tokensIn).toString());
+    assert.ok(poolData.f44Reserves.lt(before.f44Reserves));
+    assert.ok(k(poolData) >= k(before));
+    await setGlobalPause(false, false);
+
+    // deposits are proportional and rounded in the pool's favour
+    before = poolData;
+    const shares = before.lpSupply.divn(1000);
+    await program.rpc.addLiquidity(shares, new anchor.BN(10 ** 12), new anchor.BN("1000000000000000"), {
+      accounts: { ...poolAccounts(f44Mint, f44Vault), position },
+      signers: [buyer]
+    });
+    poolData = await program.account.pool.fetch(curvePool);
+    assert.equal(poolData.lpSupply.toString(), before.lpSupply.add(shares).toString());
+    assert.ok(poolData.f44Reserves.mul(before.lpSupply).gte(before.f44Reserves.mul(poolData.lpSupply)));
+    assert.ok(poolData.tokenReserves.mul(before.lpSupply).gte(before.tokenReserves.mul(poolData.lpSupply)));
+    assert.equal((await program.account.liquidityPosition.fetch(position)).shares.toString(), shares.toString());
+
+    // withdrawing the same shares never takes out more than went in
+    await program.rpc.removeLiquidity(shares, new anchor.BN(0), new anchor.BN(0), {
+      accounts: { ...poolAccounts(f44Mint, f44Vault), position },
+      signers: [buyer]
+    });
+    poolData = await program.account.pool.fetch(curvePool);
+    assert.equal(poolData.lpSupply.toString(), before.lpSupply.toString());
+    assert.ok(poolData.f44Reserves.gte(before.f44Reserves));
+    assert.ok(poolData.tokenReserves.gte(before.tokenReserves));
+    assert.ok(k(poolData) >= k(before));
+    assert.equal((await program.account.liquidityPosition.fetch(position)).shares.toNumber(), 0);
+
+    // another quote mint's vault sits at the same seed, the pool only pays out of its own
+    const otherMint = await createMint(connection, owner, owner.publicKey, null, 6);
+    const [otherConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("QUOTE-CONFIG-SEED"), otherMint.toBuffer()],
+      program.programId
+    );
+    const [otherVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("F44-VAULT-SEED"), otherMint.toBuffer()],
+      program.programId
+    );
+    await program.rpc.addQuoteMint(new anchor.BN(0), new anchor.BN(10), new anchor.BN(1), new anchor.BN(100), {
+      accounts: {
+        global,
+        quoteMint: otherMint,
+        quoteConfig: otherConfig,
+        quoteVault: otherVault,
+        authority: owner.publicKey,
+        adminSet: null,
+        adminAction: null,
+        systemProgram: SystemProgram.programId,
+        quoteTokenProgram: TOKEN_PROGRAM_ID
+      },
+      signers: [owner]
+    });
+    const buyerOther = await createAssociatedTokenAccount(connection, buyer, otherMint, buyer.publicKey);
+    await mintTo(connection, owner, otherMint, buyerOther, owner, 10 ** 6);
+    try {
+      await program.rpc.swap(new anchor.BN(10 ** 6), new anchor.BN(0), true, {
+        accounts: poolAccounts(otherMint, otherVault),
+        signers: [buyer]
+      });
+      assert.fail("the pool only takes its own quote mint");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidQuoteMint");
+    }
+
+    await queueParams({ ammProgram }, owner);
+    await apply();
+  });
+  it("An F44 curve cannot be withdrawn by the owner wallet", async() => {
+    const associatedUser = await getAssociatedTokenAddress(
+      agentMint,
+      owner.publicKey
+    );
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      owner.publicKey
+    );
+    try {
+      await program.rpc.withdraw({
+        accounts: {
+          global,
+          mint: agentMint,
+          bondingCurve,
+          associatedBondingCurve,
+          associatedUser,
+          f44Mint,
+          f44Vault,
+          quoteConfig: null,
+          associatedUserF44Account,
+          solVault: null,
+          ownerWallet: owner.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+        },
+        signers: [owner]
+      });
+      assert.fail("F44 curves leave through migrate or the built-in pool");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotWithdrawable");
+    }
+  });
+});
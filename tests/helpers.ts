@@ -0,0 +1,236 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { F44 } from "../target/types/f44";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddress,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import * as bs58 from "bs58";
+import {
+  SystemProgram,
+  Keypair,
+  PublicKey,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+
+// Shared by every test file. The files run in the order Anchor.toml lists them and build
+// on each other: `setup.ts` initializes the program, `create.ts` launches the agent curve
+// the trading tests use.
+
+// Configure the client to use the local cluster.
+anchor.setProvider(anchor.AnchorProvider.env());
+
+export const program = anchor.workspace.F44 as Program<F44>;
+export const connection = program.provider.connection;
+
+// Defind the constants for test
+export const feeRecipient = new PublicKey(
+  "8kiRRQHLcT6iRG9H3cyathqtSKhc8o7rp31wto2mvHBT"
+);
+
+export const owner = Keypair.fromSecretKey(
+  bs58.decode("KJ7yZn5AQchXPE5i74FsG1WNtccSQXgGUDtXJdyoyRa8hqSbdFH8R9NZiZnosKJnQqnRgSYvVCZqu3VqHaqF8GP")
+);
+
+export const user = Keypair.fromSecretKey(
+  bs58.decode(
+    "2LU9Gir9pDVEsUWrRHLUUdPaVM642EmMGubgyZg2LNYk1uyD4LNRR5HshCENmfTUD3nPMeN7FCJKxEdu48YSEpta"
+  )
+);
+
+export const buyer = Keypair.fromSecretKey(
+  bs58.decode(
+    "TGW9dbYndwDA5VbBBsA3AQsGtTgoCetjpJwbuCjNF3pv2J1rCXraZNrNXHhu2fxKTaNCFiotT9z3QCnujQ3WGhD"
+  )
+);
+
+const BONDING_CURVE = "BONDING-CURVE";
+const SOL_VAULT_SEED = "SOL-VAULT-SEED";
+const VAULT_SEED = "VAULT-SEED";
+// prices are F44 base units per agent token base unit, scaled by 1e18
+export const PRICE_SCALE = 1e18;
+// agent tokens are minted by `create`, 1B of them with 6 decimals
+export const AGENT_TOKEN_DECIMALS = 6;
+export const AGENT_TOKEN_SUPPLY = BigInt(1_000_000_000) * BigInt(10 ** AGENT_TOKEN_DECIMALS);
+// please assume that you already mint the F44 spl token
+export const f44Mint = new PublicKey("CxgN5z1wdKavjszkmbgAwZrgVKVKinZpPYET2T3RVkGY");
+export const NATIVE_MINT = new PublicKey("So11111111111111111111111111111111111111112");
+export const TOKEN_METADATA_PROGRAM_ID = new PublicKey(
+  "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
+);
+// workspace AMM that `migrate` seeds pools on in local tests
+export const MOCK_AMM_PROGRAM_ID = new PublicKey(
+  "BdxY3a8W3DTBGLULdAvd9nHfyNif8RQLX73BRmeCrxGF"
+);
+export const agentMetadata = {
+  name: "F44 AI Test",
+  symbol: "F44.ai",
+  uri: "https://ipfs.io/ipfs/bafkreibwclebyzx27s5tyxvutwoc2t4t3bekdgqna3bdtj76biqsenq37y",
+};
+// trading fee on every buy and sell, 1%
+export const tradeFeeBps = 100;
+// creator share of every buy and sell on the test curves, 0.5%
+export const creatorFeeBps = 50;
+// referrer share of the trading fee, 20%
+export const referralFeeBps = 2_000;
+// curves graduate once their market cap passes 100,000 F44 unless the creator picks
+// another threshold between 1 and 1,000,000,000 F44
+export const graduationThreshold = new anchor.BN(100_000 * 10 ** 6);
+export const minGraduationThreshold = new anchor.BN(10 ** 6);
+export const maxGraduationThreshold = new anchor.BN(10 ** 15);
+
+export const [global] = PublicKey.findProgramAddressSync(
+  [Buffer.from("GLOBAL-STATE-SEED")],
+  program.programId
+);
+export const [f44Vault] = PublicKey.findProgramAddressSync(
+  [Buffer.from("F44-VAULT-SEED"), f44Mint.toBuffer()],
+  program.programId
+);
+// created by `setup.ts`, F44 trading fees are paid here
+export const feeRecipientAccount = getAssociatedTokenAddressSync(f44Mint, feeRecipient);
+
+// Addresses of the curve `user` launches with `nonce`.
+export const curveAddresses = (nonce: anchor.BN) => {
+  const [mint] = PublicKey.findProgramAddressSync(
+    [Buffer.from("mint"), user.publicKey.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  );
+  const [curve] = PublicKey.findProgramAddressSync(
+    [Buffer.from(BONDING_CURVE), mint.toBuffer()],
+    program.programId
+  );
+  const [vault] = PublicKey.findProgramAddressSync(
+    [Buffer.from(VAULT_SEED), mint.toBuffer()],
+    program.programId
+  );
+  const [curvePool] = PublicKey.findProgramAddressSync(
+    [Buffer.from("POOL-SEED"), mint.toBuffer()],
+    program.programId
+  );
+  const [solVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from(SOL_VAULT_SEED), mint.toBuffer()],
+    program.programId
+  );
+  return { mint, curve, vault, curvePool, solVault };
+};
+
+// The SPL Token agent curve launched by `create.ts` and traded on by the later files.
+export const mintNonce = new anchor.BN(Date.now());
+const agent = curveAddresses(mintNonce);
+export const agentMint = agent.mint;
+export const bondingCurve = agent.curve;
+export const associatedBondingCurve = agent.vault;
+export const pool = agent.curvePool;
+export const [metadata] = PublicKey.findProgramAddressSync(
+  [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), agentMint.toBuffer()],
+  TOKEN_METADATA_PROGRAM_ID
+);
+
+// `set_params` takes partial updates, fields left out keep their current value
+const PARAMS_FIELDS = [
+  "feeRecipient", "ownerWallet", "feeAmount", "ammProgram",
+  "tradeFeeBps", "referralFeeBps", "graduationMetric", "graduationThreshold",
+  "minGraduationThreshold", "maxGraduationThreshold", "paramsDelay",
+];
+// the multisig's `ParamsUpdate` also carries the `set_oracle` fields
+const PARAMS_UPDATE_FIELDS = [
+  ...PARAMS_FIELDS,
+  "priceOracle", "usdGraduationThreshold", "oracleMaxAge", "oracleMaxConfidenceBps",
+];
+export const paramsUpdate = (changes: Record<string, any>) =>
+  Object.fromEntries(PARAMS_UPDATE_FIELDS.map((field) => [field, changes[field] ?? null]));
+export const queueParams = (changes: Record<string, any>, signer: Keypair) =>
+  (program.rpc.setParams as any)(
+    ...PARAMS_FIELDS.map((field) => changes[field] ?? null), {
+      accounts: { global, user: signer.publicKey },
+      signers: [signer]
+    }
+  );
+
+// Launches an F44 curve on a Token-2022 mint from `user`, for the tests that graduate one.
+export const launchCurve = async (nonce: anchor.BN) => {
+  const { mint, curve, vault, curvePool } = curveAddresses(nonce);
+
+  await program.rpc.create(
+    { linear: { initialPrice: new anchor.BN("1000000000000"), curveSlope: new anchor.BN("1000") } },
+    nonce,
+    agentMetadata.name,
+    agentMetadata.symbol,
+    agentMetadata.uri,
+    new anchor.BN(creatorFeeBps),
+    null, {
+      accounts: {
+        user: user.publicKey,
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedBondingCurve: vault,
+        metadata: null,
+        f44Mint,
+        f44Vault,
+        quoteConfig: null,
+        associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, user.publicKey),
+        quoteMint: f44Mint,
+        solVault: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenMetadataProgram: null,
+        rent: SYSVAR_RENT_PUBKEY
+      },
+      signers: [user]
+    }
+  );
+  return { mint, curve, vault, curvePool };
+};
+
+// `buyer` asks for the whole supply of a launched curve, the buy stops where it graduates.
+// Returns the buyer's agent token account.
+export const completeCurve = async ({ mint, curve, vault, curvePool }: Awaited<ReturnType<typeof launchCurve>>) => {
+  const associatedUser = getAssociatedTokenAddressSync(
+    mint,
+    buyer.publicKey,
+    false,
+    TOKEN_2022_PROGRAM_ID
+  );
+  const associatedUserF44Account = await getAssociatedTokenAddress(
+    f44Mint,
+    buyer.publicKey
+  );
+  const balance = (await getAccount(connection, associatedUserF44Account)).amount;
+  const requested = (await program.account.bondingCurve.fetch(curve)).tokenTotalSupply;
+
+  await program.rpc.buy(requested, new anchor.BN(balance.toString()), {
+    accounts: {
+      global,
+      mint,
+      bondingCurve: curve,
+      associatedBondingCurve: vault,
+      associatedUser,
+      f44Mint,
+      f44Vault,
+      quoteConfig: null,
+      associatedUserF44Account,
+      feeRecipient: feeRecipientAccount,
+      referrer: null,
+      referral: null,
+      priceOracle: null,
+      pool: curvePool,
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    },
+    signers: [buyer]
+  });
+  return associatedUser;
+};
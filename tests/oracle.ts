@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  PublicKey,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  owner,
+  buyer,
+  f44Mint,
+  global,
+  f44Vault,
+  feeRecipientAccount,
+  agentMint,
+  bondingCurve,
+  associatedBondingCurve,
+  pool,
+} from "./helpers";
+
+describe("oracle", () => {
+  it("With a USD oracle configured buys must pass the price account", async() => {
+    const [priceFeed] = PublicKey.findProgramAddressSync(
+      [Buffer.from("PRICE-FEED-SEED")],
+      program.programId
+    );
+    // 1 F44 = $0.50
+    await program.rpc.pushPrice(new anchor.BN(50_000_000), new anchor.BN(10_000), -8, {
+      accounts: {
+        global,
+        priceFeed,
+        authority: owner.publicKey,
+        adminSet: null,
+        adminAction: null,
+        systemProgram: SystemProgram.programId,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [owner]
+    });
+    await program.rpc.setOracle(priceFeed, new anchor.BN(100_000), new anchor.BN(60), new anchor.BN(100), {
+      accounts: { global, user: owner.publicKey },
+      signers: [owner]
+    });
+    // queued behind the timelock like any params change, the delay is 0 here
+    assert.ok((await program.account.global.fetch(global)).priceOracle.equals(PublicKey.default));
+    await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+    assert.ok((await program.account.global.fetch(global)).priceOracle.equals(priceFeed));
+
+    const accounts = {
+      global,
+      mint: agentMint,
+      bondingCurve,
+      associatedBondingCurve,
+      associatedUser: await getAssociatedTokenAddress(agentMint, buyer.publicKey),
+      f44Mint,
+      f44Vault,
+      quoteConfig: null,
+      associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, buyer.publicKey),
+      feeRecipient: feeRecipientAccount,
+      referrer: null,
+      referral: null,
+      priceOracle: null,
+      pool,
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    };
+    try {
+      await program.rpc.buy(new anchor.BN(1_000_000), new anchor.BN(10 ** 9), {
+        accounts,
+        signers: [buyer]
+      });
+      assert.fail("buy should require the oracle account");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidOracle");
+    } finally {
+      await program.rpc.setOracle(PublicKey.default, new anchor.BN(0), new anchor.BN(0), new anchor.BN(0), {
+        accounts: { global, user: owner.publicKey },
+        signers: [owner]
+      });
+      await program.rpc.applyPendingParams({ accounts: { global, clock: SYSVAR_CLOCK_PUBKEY } });
+    }
+
+    const feed = await program.account.priceFeed.fetch(priceFeed);
+    assert.equal(feed.price.toNumber(), 50_000_000);
+    assert.equal(feed.expo, -8);
+  });
+});
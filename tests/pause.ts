@@ -0,0 +1,116 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  owner,
+  buyer,
+  f44Mint,
+  global,
+  f44Vault,
+  feeRecipientAccount,
+  agentMint,
+  bondingCurve,
+  associatedBondingCurve,
+  pool,
+} from "./helpers";
+
+describe("pause", () => {
+  it("A pause stops buys while sell-only still lets holders exit", async() => {
+    const associatedUser = await getAssociatedTokenAddress(agentMint, buyer.publicKey);
+    const associatedUserF44Account = await getAssociatedTokenAddress(f44Mint, buyer.publicKey);
+    const tradeAccounts = {
+      global,
+      mint: agentMint,
+      bondingCurve,
+      associatedBondingCurve,
+      associatedUser,
+      f44Mint,
+      f44Vault,
+      associatedUserF44Account,
+      quoteConfig: null,
+      feeRecipient: feeRecipientAccount,
+      referrer: null,
+      referral: null,
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    };
+    const buy = () => program.rpc.buy(new anchor.BN(10 ** 6), new anchor.BN(10 ** 12), {
+      accounts: { ...tradeAccounts, priceOracle: null, pool },
+      signers: [buyer]
+    });
+    const sell = () => program.rpc.sell(new anchor.BN(10 ** 6), new anchor.BN(0), {
+      accounts: tradeAccounts,
+      signers: [buyer]
+    });
+    const setGlobalPause = (paused: boolean, sellOnly: boolean) =>
+      program.rpc.setGlobalPause(paused, sellOnly, "incident response", {
+        accounts: { global, authority: owner.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+        signers: [owner]
+      });
+    const setCurvePause = (paused: boolean, sellOnly: boolean) =>
+      program.rpc.setCurvePause(paused, sellOnly, "curve under review", {
+        accounts: {
+          global,
+          mint: agentMint,
+          bondingCurve,
+          authority: owner.publicKey,
+          adminSet: null,
+          adminAction: null,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [owner]
+      });
+    const expectPaused = async (tx: () => Promise<string>) => {
+      try {
+        await tx();
+        assert.fail("the pause should reject this");
+      } catch (error) {
+        assert.equal(error.error?.errorCode?.code, "Paused");
+      }
+    };
+
+    await buy();
+
+    // a full pause stops both sides
+    await setGlobalPause(true, false);
+    await expectPaused(buy);
+    await expectPaused(sell);
+
+    // sell-only is the safe exit
+    await setGlobalPause(true, true);
+    await expectPaused(buy);
+    await sell();
+    await setGlobalPause(false, false);
+    assert.equal((await program.account.global.fetch(global)).paused, false);
+
+    // the same on a single curve
+    await setCurvePause(true, true);
+    await expectPaused(buy);
+    await sell();
+    await setCurvePause(false, false);
+    await buy();
+
+    try {
+      await program.rpc.setGlobalPause(true, false, "x".repeat(201), {
+        accounts: { global, authority: owner.publicKey, clock: SYSVAR_CLOCK_PUBKEY },
+        signers: [owner]
+      });
+      assert.fail("the reason is too long");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidPauseReason");
+    }
+  });
+});
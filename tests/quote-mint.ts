@@ -0,0 +1,268 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createMint,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  PublicKey,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  connection,
+  feeRecipient,
+  owner,
+  user,
+  buyer,
+  f44Mint,
+  agentMetadata,
+  creatorFeeBps,
+  global,
+  f44Vault,
+  feeRecipientAccount,
+  curveAddresses,
+} from "./helpers";
+
+describe("quote mints", () => {
+  it("Curves can be launched against a whitelisted quote mint", async() => {
+    const quoteMint = await createMint(connection, owner, owner.publicKey, null, 6);
+    const [quoteConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("QUOTE-CONFIG-SEED"), quoteMint.toBuffer()],
+      program.programId
+    );
+    const [quoteVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("F44-VAULT-SEED"), quoteMint.toBuffer()],
+      program.programId
+    );
+    const quoteCreateFee = new anchor.BN(5 * 10 ** 6);
+    const quoteThreshold = new anchor.BN(10 ** 15);
+    await program.rpc.addQuoteMint(
+      quoteCreateFee,
+      quoteThreshold,
+      new anchor.BN(1),
+      new anchor.BN(10 ** 15), {
+        accounts: {
+          global,
+          quoteMint,
+          quoteConfig,
+          quoteVault,
+          authority: owner.publicKey,
+          adminSet: null,
+          adminAction: null,
+          systemProgram: SystemProgram.programId,
+          quoteTokenProgram: TOKEN_PROGRAM_ID
+        },
+        signers: [owner]
+      }
+    );
+
+    const userQuoteAccount = await createAssociatedTokenAccount(connection, user, quoteMint, user.publicKey);
+    await mintTo(connection, owner, quoteMint, userQuoteAccount, owner, 100 * 10 ** 6);
+
+    // the quote vault shares the F44 vault seed, but deposits only count towards the F44 fees
+    try {
+      await program.rpc.deposit(new anchor.BN(10 ** 6), {
+        accounts: {
+          global,
+          owner: user.publicKey,
+          f44Mint: quoteMint,
+          f44Vault: quoteVault,
+          associatedOwnerAccount: userQuoteAccount,
+          systemProgram: SystemProgram.programId,
+          f44TokenProgram: TOKEN_PROGRAM_ID
+        },
+        signers: [user]
+      });
+      assert.fail("deposit should reject a quote mint other than F44");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidQuoteMint");
+    }
+
+    const buyerQuoteAccount = await createAssociatedTokenAccount(connection, buyer, quoteMint, buyer.publicKey);
+    await mintTo(connection, owner, quoteMint, buyerQuoteAccount, owner, 1_000_000 * 10 ** 6);
+    const feeRecipientQuoteAccount = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      buyer,
+      quoteMint,
+      feeRecipient
+    )).address;
+
+    const nonce = new anchor.BN(Date.now() + 5);
+    const { mint, curve, vault, curvePool } = curveAddresses(nonce);
+    // the create fee is paid in the quote mint
+    await program.rpc.create(
+      { linear: { initialPrice: new anchor.BN("1000000000000"), curveSlope: new anchor.BN("1000") } },
+      nonce,
+      agentMetadata.name,
+      agentMetadata.symbol,
+      agentMetadata.uri,
+      new anchor.BN(creatorFeeBps),
+      null, {
+        accounts: {
+          user: user.publicKey,
+          global,
+          mint,
+          bondingCurve: curve,
+          associatedBondingCurve: vault,
+          metadata: null,
+          f44Mint: quoteMint,
+          f44Vault: quoteVault,
+          associatedUserF44Account: userQuoteAccount,
+          quoteMint,
+          solVault: null,
+          quoteConfig,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: null,
+          rent: SYSVAR_RENT_PUBKEY
+        },
+        signers: [user]
+      }
+    );
+    let curveData = await program.account.bondingCurve.fetch(curve);
+    assert.ok(curveData.quoteMint.equals(quoteMint));
+    assert.equal(curveData.graduationThreshold.toString(), quoteThreshold.toString());
+
+    const f44SupplyBefore = (await program.account.global.fetch(global)).f44Supply;
+    const accounts = {
+      global,
+      mint,
+      bondingCurve: curve,
+      associatedBondingCurve: vault,
+      associatedUser: getAssociatedTokenAddressSync(mint, buyer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      f44Mint: quoteMint,
+      f44Vault: quoteVault,
+      associatedUserF44Account: buyerQuoteAccount,
+      feeRecipient: feeRecipientQuoteAccount,
+      referrer: null,
+      referral: null,
+      priceOracle: null,
+      quoteConfig,
+      pool: curvePool,
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    };
+    await program.rpc.buy(new anchor.BN(100_000 * 10 ** 6), new anchor.BN(100 * 10 ** 6), {
+      accounts,
+      signers: [buyer]
+    });
+
+    // the quote mint's vault is accounted on its config, F44 accounting is untouched
+    curveData = await program.account.bondingCurve.fetch(curve);
+    const configData = await program.account.quoteConfig.fetch(quoteConfig);
+    const vaultBalance = (await getAccount(connection, quoteVault)).amount;
+    assert.equal(configData.protocolFees.toString(), quoteCreateFee.toString());
+    assert.equal(
+      configData.supply.toString(),
+      curveData.f44Reserves.add(curveData.creatorFees).toString()
+    );
+    assert.equal(vaultBalance.toString(), configData.supply.add(configData.protocolFees).toString());
+    assert.equal(
+      (await program.account.global.fetch(global)).f44Supply.toString(),
+      f44SupplyBefore.toString()
+    );
+
+    // the curve only trades against its own quote mint
+    try {
+      await program.rpc.buy(new anchor.BN(10 ** 6), new anchor.BN(10 ** 9), {
+        accounts: {
+          ...accounts,
+          f44Mint,
+          f44Vault,
+          associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, buyer.publicKey),
+          feeRecipient: feeRecipientAccount,
+          quoteConfig: null
+        },
+        signers: [buyer]
+      });
+      assert.fail("buy should reject a quote mint other than the curve's");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidQuoteMint");
+    }
+
+    // fee and threshold changes wait out the params timelock, the delay is 0 here
+    await program.rpc.updateQuoteMint(
+      true,
+      quoteCreateFee.muln(2),
+      quoteThreshold,
+      new anchor.BN(1),
+      new anchor.BN(10 ** 15), {
+        accounts: {
+          global,
+          quoteConfig,
+          authority: owner.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          adminSet: null,
+          adminAction: null
+        },
+        signers: [owner]
+      }
+    );
+    let queued = await program.account.quoteConfig.fetch(quoteConfig);
+    assert.equal(queued.createFee.toString(), quoteCreateFee.toString());
+    assert.equal(queued.pendingParams.createFee.toString(), quoteCreateFee.muln(2).toString());
+    await program.rpc.applyQuoteMintUpdate({ accounts: { quoteConfig, clock: SYSVAR_CLOCK_PUBKEY } });
+    queued = await program.account.quoteConfig.fetch(quoteConfig);
+    assert.equal(queued.createFee.toString(), quoteCreateFee.muln(2).toString());
+    assert.equal(queued.pendingParamsAt.toNumber(), 0);
+
+    // only the authority drops a queued update, after which nothing is left to apply
+    await program.rpc.updateQuoteMint(
+      true,
+      quoteCreateFee,
+      quoteThreshold,
+      new anchor.BN(1),
+      new anchor.BN(10 ** 15), {
+        accounts: {
+          global,
+          quoteConfig,
+          authority: owner.publicKey,
+          clock: SYSVAR_CLOCK_PUBKEY,
+          adminSet: null,
+          adminAction: null
+        },
+        signers: [owner]
+      }
+    );
+    const cancelAccounts = (authority: PublicKey) => ({
+      global,
+      quoteConfig,
+      authority,
+      adminSet: null,
+      adminAction: null
+    });
+    try {
+      await program.rpc.cancelQuoteMintUpdate({ accounts: cancelAccounts(buyer.publicKey), signers: [buyer] });
+      assert.fail("only the authority cancels");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NotAuthorized");
+    }
+    await program.rpc.cancelQuoteMintUpdate({ accounts: cancelAccounts(owner.publicKey), signers: [owner] });
+    queued = await program.account.quoteConfig.fetch(quoteConfig);
+    assert.equal(queued.pendingParamsAt.toNumber(), 0);
+    assert.equal(queued.createFee.toString(), quoteCreateFee.muln(2).toString());
+    try {
+      await program.rpc.applyQuoteMintUpdate({ accounts: { quoteConfig, clock: SYSVAR_CLOCK_PUBKEY } });
+      assert.fail("nothing is queued after a cancel");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "NoPendingParams");
+    }
+  });
+});
@@ -0,0 +1,140 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  getAccount,
+  getAssociatedTokenAddress,
+  getOrCreateAssociatedTokenAccount,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  connection,
+  feeRecipient,
+  owner,
+  buyer,
+  f44Mint,
+  MOCK_AMM_PROGRAM_ID,
+  tradeFeeBps,
+  referralFeeBps,
+  graduationThreshold,
+  minGraduationThreshold,
+  maxGraduationThreshold,
+  global,
+  f44Vault,
+  feeRecipientAccount,
+} from "./helpers";
+
+describe("setup", () => {
+  it("Is initialized!", async () => {
+    try {
+      const tx = await program.rpc.initialize({
+        accounts: {
+          global,
+          owner: owner.publicKey,
+          f44Mint,
+          f44Vault,
+          systemProgram: SystemProgram.programId,
+          f44TokenProgram: TOKEN_PROGRAM_ID
+        },
+        signers: [owner]
+      });
+      console.log("Initialize Tx->", tx);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("set params", async () => {
+    // `withdraw` is signed by the owner wallet
+    const ownerWallet = owner.publicKey;
+    const feeAmount = 1000; // The user should pay 1,000 F44 token when create the pool
+
+    try {
+      const tx = await program.rpc.setParams(
+        feeRecipient,
+        ownerWallet,
+        new anchor.BN(feeAmount),
+        MOCK_AMM_PROGRAM_ID,
+        new anchor.BN(tradeFeeBps),
+        new anchor.BN(referralFeeBps),
+        { marketCap: {} },
+        graduationThreshold,
+        minGraduationThreshold,
+        maxGraduationThreshold,
+        new anchor.BN(0),
+        {
+          accounts: {
+            global,
+            user: owner.publicKey
+          },
+          signers: [owner]
+        }
+      );
+      // no delay is set yet, so the change can be applied right away
+      await program.rpc.applyPendingParams({
+        accounts: {
+          global,
+          clock: SYSVAR_CLOCK_PUBKEY
+        }
+      });
+      const globalData = await program.account.global.fetch(global);
+      console.log("globalData->", {
+        initialized: globalData.initialized,
+        authority: globalData.authority.toBase58(),
+        feeRecipient: globalData.feeRecipient.toBase58(),
+        ownerWallet: globalData.ownerWallet.toBase58(),
+        f44Mint: globalData.f44Mint.toBase58(),
+        f44Vault: globalData.f44Vault.toBase58(),
+        f44Supply: parseInt(globalData.f44Supply.toString()),
+        protocolFees: parseInt(globalData.protocolFees.toString()),
+        feeAmount: parseInt(globalData.feeAmount.toString()),
+        ammProgram: globalData.ammProgram.toBase58(),
+        tradeFeeBps: parseInt(globalData.tradeFeeBps.toString()),
+        referralFeeBps: parseInt(globalData.referralFeeBps.toString()),
+        graduationMetric: globalData.graduationMetric,
+        graduationThreshold: globalData.graduationThreshold.toString()
+      });
+      console.log("tx->", tx);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("Deposit F44 tokens to the vault PDA controlled by the contract", async() => {
+    try {
+      const amount = 1000000 * (10 ** 6);
+      const associatedOwnerAccount = await getAssociatedTokenAddress(
+        f44Mint,
+        owner.publicKey
+      );
+
+      const tx = await program.rpc.deposit(
+        new anchor.BN(amount), {
+          accounts: {
+            global,
+            owner: owner.publicKey,
+            f44Mint,f44Vault,
+            associatedOwnerAccount,
+            systemProgram: SystemProgram.programId,
+            f44TokenProgram: TOKEN_PROGRAM_ID
+          },
+          signers: [owner]
+        }
+      );
+      console.log("Transaction was success and hash is ", tx);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("The fee recipient has an F44 account for the trading fees", async() => {
+    await getOrCreateAssociatedTokenAccount(
+      connection,
+      buyer,
+      f44Mint,
+      feeRecipient
+    );
+    assert.ok((await getAccount(connection, feeRecipientAccount)).owner.equals(feeRecipient));
+  });
+});
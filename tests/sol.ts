@@ -0,0 +1,362 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+  getAccount,
+  getAssociatedTokenAddressSync,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  Transaction,
+  SYSVAR_RENT_PUBKEY,
+  SYSVAR_CLOCK_PUBKEY,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  connection,
+  feeRecipient,
+  owner,
+  user,
+  buyer,
+  f44Mint,
+  NATIVE_MINT,
+  agentMetadata,
+  tradeFeeBps,
+  creatorFeeBps,
+  maxGraduationThreshold,
+  global,
+  f44Vault,
+  curveAddresses,
+} from "./helpers";
+
+describe("SOL curves", () => {
+  it("A SOL curve trades in lamports through its SOL vault", async() => {
+    const nonce = new anchor.BN(Date.now() + 4);
+    const { mint, curve, vault, solVault } = curveAddresses(nonce);
+
+    // lamport fees are swept to the fee recipient wallet itself, which has to stay rent-exempt
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(SystemProgram.transfer({
+        fromPubkey: buyer.publicKey,
+        toPubkey: feeRecipient,
+        lamports: 10 ** 7,
+      })),
+      [buyer]
+    );
+
+    const linear = { linear: { initialPrice: new anchor.BN("1000000000000"), curveSlope: new anchor.BN("1000") } };
+    const createAccounts = {
+      user: user.publicKey,
+      global,
+      mint,
+      bondingCurve: curve,
+      associatedBondingCurve: vault,
+      metadata: null,
+      f44Mint,
+      f44Vault,
+      quoteConfig: null,
+      associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, user.publicKey),
+      quoteMint: NATIVE_MINT,
+      solVault,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenMetadataProgram: null,
+      rent: SYSVAR_RENT_PUBKEY
+    };
+
+    // the global default threshold is in F44, so a SOL curve has to name its own
+    try {
+      await program.rpc.create(
+        linear, nonce, agentMetadata.name, agentMetadata.symbol, agentMetadata.uri,
+        new anchor.BN(creatorFeeBps), null,
+        { accounts: createAccounts, signers: [user] }
+      );
+      assert.fail("create should require a lamport threshold for a SOL curve");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidGraduationThreshold");
+    }
+
+    // 1 lamport per whole token to start, the threshold is in lamports
+    await program.rpc.create(
+      linear,
+      nonce,
+      agentMetadata.name,
+      agentMetadata.symbol,
+      agentMetadata.uri,
+      new anchor.BN(creatorFeeBps),
+      maxGraduationThreshold,
+      { accounts: createAccounts, signers: [user] }
+    );
+    const rent = await connection.getMinimumBalanceForRentExemption(0);
+    assert.equal(await connection.getBalance(solVault), rent);
+
+    const associatedUser = getAssociatedTokenAddressSync(
+      mint,
+      buyer.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const amount = new anchor.BN(100_000 * 10 ** 6);
+    await program.rpc.buySol(amount, new anchor.BN(10 ** 9), {
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedBondingCurve: vault,
+        associatedUser,
+        solVault,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+
+    let curveData = await program.account.bondingCurve.fetch(curve);
+    assert.ok(curveData.quoteMint.equals(NATIVE_MINT));
+    assert.equal(curveData.tokenReserves.toString(), amount.toString());
+    // the vault holds the reserves, the creator fee and the trading fee on top of its rent
+    assert.equal(
+      await connection.getBalance(solVault),
+      rent + curveData.f44Reserves.toNumber() + curveData.creatorFees.toNumber() + curveData.protocolFees.toNumber()
+    );
+    assert.equal(
+      curveData.protocolFees.toNumber(),
+      Math.ceil(curveData.f44Reserves.toNumber() * tradeFeeBps / 10_000)
+    );
+
+    // F44 instructions refuse a SOL curve
+    try {
+      await program.rpc.claimCreatorFees({
+        accounts: {
+          global,
+          mint,
+          bondingCurve: curve,
+          f44Mint,
+          f44Vault,
+          quoteConfig: null,
+          associatedCreatorF44Account: await getAssociatedTokenAddress(f44Mint, user.publicKey),
+          creator: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          clock: SYSVAR_CLOCK_PUBKEY
+        },
+        signers: [user]
+      });
+      assert.fail("claim_creator_fees should reject a SOL curve");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "InvalidQuoteMint");
+    }
+
+    // selling everything back pays out less than was paid in
+    const quote = await program.methods.quoteSell(amount).accounts({
+      global, mint, bondingCurve: curve, associatedBondingCurve: vault, f44Mint, priceOracle: null,
+    }).view();
+    const buyerBefore = await connection.getBalance(buyer.publicKey);
+    await program.rpc.sellSol(amount, quote.f44Received, {
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedUser,
+        solVault,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    curveData = await program.account.bondingCurve.fetch(curve);
+    assert.equal(curveData.tokenReserves.toNumber(), 0);
+    assert.ok((await connection.getBalance(buyer.publicKey)) > buyerBefore);
+
+    // the creator takes their lamport fees out of the vault
+    await program.rpc.claimCreatorFeesSol({
+      accounts: {
+        mint,
+        bondingCurve: curve,
+        solVault,
+        creator: user.publicKey,
+        systemProgram: SystemProgram.programId,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [user]
+    });
+    curveData = await program.account.bondingCurve.fetch(curve);
+    assert.equal(curveData.creatorFees.toNumber(), 0);
+
+    // anyone sweeps the trading fees to the fee recipient
+    const protocolFees = curveData.protocolFees.toNumber();
+    const feeBefore = await connection.getBalance(feeRecipient);
+    await program.rpc.claimProtocolFeesSol({
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        solVault,
+        feeRecipient,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    curveData = await program.account.bondingCurve.fetch(curve);
+    assert.equal(curveData.protocolFees.toNumber(), 0);
+    assert.equal((await connection.getBalance(feeRecipient)) - feeBefore, protocolFees);
+    assert.equal(await connection.getBalance(solVault), rent + curveData.f44Reserves.toNumber());
+
+    // exact-in buys and exact-out sells settle in lamports the same way
+    const spend = 10 ** 7;
+    let lamportsBefore = await connection.getBalance(buyer.publicKey);
+    await program.rpc.buySolExactIn(new anchor.BN(spend), new anchor.BN(1), {
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedBondingCurve: vault,
+        associatedUser,
+        solVault,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    assert.ok(lamportsBefore - (await connection.getBalance(buyer.publicKey)) <= spend);
+    const bought = (await getAccount(connection, associatedUser, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+    assert.ok(bought > BigInt(0));
+
+    const receive = spend / 4;
+    lamportsBefore = await connection.getBalance(buyer.publicKey);
+    await program.rpc.sellSolExactOut(new anchor.BN(receive), new anchor.BN(bought.toString()), {
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedUser,
+        solVault,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    assert.ok((await connection.getBalance(buyer.publicKey)) - lamportsBefore >= receive);
+  });
+  it("The owner wallet settles a completed SOL curve once", async() => {
+    const nonce = new anchor.BN(Date.now() + 8);
+    const { mint, curve, vault, solVault } = curveAddresses(nonce);
+
+    await program.rpc.create(
+      { linear: { initialPrice: new anchor.BN("1000000000000"), curveSlope: new anchor.BN("1000") } },
+      nonce,
+      agentMetadata.name,
+      agentMetadata.symbol,
+      agentMetadata.uri,
+      new anchor.BN(creatorFeeBps),
+      new anchor.BN(10 ** 9),
+      {
+        accounts: {
+          user: user.publicKey,
+          global,
+          mint,
+          bondingCurve: curve,
+          associatedBondingCurve: vault,
+          metadata: null,
+          f44Mint,
+          f44Vault,
+          quoteConfig: null,
+          associatedUserF44Account: await getAssociatedTokenAddress(f44Mint, user.publicKey),
+          quoteMint: NATIVE_MINT,
+          solVault,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          f44TokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: null,
+          rent: SYSVAR_RENT_PUBKEY
+        },
+        signers: [user]
+      }
+    );
+
+    // asks for far more than the threshold allows, the fill stops where the curve completes
+    await program.rpc.buySol(new anchor.BN(10 ** 8).mul(new anchor.BN(10 ** 6)), new anchor.BN(5 * 10 ** 9), {
+      accounts: {
+        global,
+        mint,
+        bondingCurve: curve,
+        associatedBondingCurve: vault,
+        associatedUser: getAssociatedTokenAddressSync(mint, buyer.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        solVault,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    const before = await program.account.bondingCurve.fetch(curve);
+    assert.ok(before.complete);
+    const tokensLeft = (await getAccount(connection, vault, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+
+    const associatedUser = getAssociatedTokenAddressSync(mint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const withdrawAccounts = {
+      global,
+      mint,
+      bondingCurve: curve,
+      associatedBondingCurve: vault,
+      associatedUser,
+      f44Mint,
+      f44Vault,
+      quoteConfig: null,
+      associatedUserF44Account: getAssociatedTokenAddressSync(f44Mint, owner.publicKey),
+      solVault,
+      ownerWallet: owner.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID
+    };
+    await program.rpc.withdraw({ accounts: withdrawAccounts, signers: [owner] });
+
+    // the whole reserves and every token left go out, the fees stay claimable
+    const after = await program.account.bondingCurve.fetch(curve);
+    assert.ok(after.migrated);
+    assert.equal(after.f44Reserves.toNumber(), 0);
+    assert.equal(after.creatorFees.toString(), before.creatorFees.toString());
+    assert.equal(after.protocolFees.toString(), before.protocolFees.toString());
+    const rent = await connection.getMinimumBalanceForRentExemption(0);
+    assert.equal(
+      await connection.getBalance(solVault),
+      rent + after.creatorFees.toNumber() + after.protocolFees.toNumber()
+    );
+    assert.equal(
+      (await getAccount(connection, associatedUser, undefined, TOKEN_2022_PROGRAM_ID)).amount.toString(),
+      tokensLeft.toString()
+    );
+
+    try {
+      await program.rpc.withdraw({ accounts: withdrawAccounts, signers: [owner] });
+      assert.fail("a curve is settled once");
+    } catch (error) {
+      assert.equal(error.error?.errorCode?.code, "AlreadyMigrated");
+    }
+  });
+});
@@ -0,0 +1,473 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddress,
+  getAccount,
+} from "@solana/spl-token";
+import {
+  SystemProgram,
+  PublicKey,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import assert from "assert";
+import {
+  program,
+  connection,
+  user,
+  buyer,
+  PRICE_SCALE,
+  f44Mint,
+  tradeFeeBps,
+  creatorFeeBps,
+  global,
+  f44Vault,
+  feeRecipientAccount,
+  agentMint,
+  bondingCurve,
+  associatedBondingCurve,
+  pool,
+  launchCurve,
+  completeCurve,
+} from "./helpers";
+
+describe("trade", () => {
+  it("Buy agent Token with F44 token", async() => {
+    try {
+      const f44Amount = 10;
+      const amount = await calculateBuyF44Cost(f44Amount);
+      console.log("The agent token amount that we can buy with f44 Amount is ", amount);
+      const slippage = 1; //1%
+      // the trading fee is paid on top of the cost
+      const maxF44Amount = f44Amount * (100 + slippage) / 100 * (10_000 + tradeFeeBps + creatorFeeBps) / 10_000 * 10 ** 6;
+      console.log("max f44 amount is ", maxF44Amount);
+      const associatedUser = await getAssociatedTokenAddress(
+        agentMint,
+        buyer.publicKey
+      );
+      const associatedUserF44Account = await getAssociatedTokenAddress(
+        f44Mint,
+        buyer.publicKey
+      );
+      const tx = await program.rpc.buy(
+        new anchor.BN(parseInt((amount * 10 ** 6).toString())),
+        new anchor.BN(parseInt(maxF44Amount.toString())), {
+          accounts: {
+            global,
+            mint: agentMint,
+            bondingCurve,
+            associatedBondingCurve,
+            associatedUser,
+            f44Mint,
+            f44Vault,associatedUserF44Account,
+            quoteConfig: null,
+            feeRecipient: feeRecipientAccount,
+            referrer: null,
+            referral: null,
+            priceOracle: null,
+            pool,
+            user: buyer.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            f44TokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY
+          },
+          signers: [buyer]
+        }
+      );
+      console.log("Buy tx hash is ", tx);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("Sell agent token", async() => {
+    try {
+      const tokenAmount = 700;
+      const amount = await calculateF44SellCost(tokenAmount);
+      console.log("The agent token amount that we can buy with f44 Amount is ", amount);
+      const slippage = 1; //1%
+      // the trading fee is taken out of the proceeds
+      const minF44Amount = amount * (100 - slippage) / 100 * (10_000 - tradeFeeBps - creatorFeeBps) / 10_000 * 10 ** 6;
+
+      const associatedUser = await getAssociatedTokenAddress(
+        agentMint,
+        buyer.publicKey
+      );
+      const associatedUserF44Account = await getAssociatedTokenAddress(
+        f44Mint,
+        buyer.publicKey
+      );
+
+      const tx = await program.rpc.sell(
+        new anchor.BN(parseInt((tokenAmount * 10 ** 6).toString())),
+        new anchor.BN(parseInt(minF44Amount.toString())), {
+          accounts: {
+            global,
+            mint: agentMint,
+            bondingCurve,
+            associatedBondingCurve,
+            associatedUser,
+            f44Mint,
+            f44Vault,
+            quoteConfig: null,
+            associatedUserF44Account,
+            feeRecipient: feeRecipientAccount,
+            referrer: null,
+            referral: null,
+            user: buyer.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            f44TokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            clock: SYSVAR_CLOCK_PUBKEY
+          },
+          signers: [buyer]
+        }
+      );
+      console.log("Sell tx hash is ", tx);
+    } catch (error) {
+      console.log(error);
+    }
+  });
+  it("Buying then selling the same amount never returns more F44 than was paid", async() => {
+    const tokenAmount = new anchor.BN(123_456_789);
+    const associatedUser = await getAssociatedTokenAddress(
+      agentMint,
+      buyer.publicKey
+    );
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      buyer.publicKey
+    );
+    const accounts = {
+      global,
+      mint: agentMint,
+      bondingCurve,
+      associatedBondingCurve,
+      associatedUser,
+      f44Mint,
+      f44Vault,
+      quoteConfig: null,
+      associatedUserF44Account,
+      feeRecipient: feeRecipientAccount,
+      referrer: null,
+      referral: null,
+      priceOracle: null,
+      pool,
+      user: buyer.publicKey,
+      systemProgram: SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      f44TokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      clock: SYSVAR_CLOCK_PUBKEY
+    };
+
+    const before = (await getAccount(connection, associatedUserF44Account)).amount;
+    await program.rpc.buy(tokenAmount, new anchor.BN(before.toString()), {
+      accounts,
+      signers: [buyer]
+    });
+    const afterBuy = (await getAccount(connection, associatedUserF44Account)).amount;
+    await program.rpc.sell(tokenAmount, new anchor.BN(0), {
+      accounts,
+      signers: [buyer]
+    });
+    const afterSell = (await getAccount(connection, associatedUserF44Account)).amount;
+
+    const paid = before - afterBuy;
+    const received = afterSell - afterBuy;
+    console.log(`paid ${paid} F44 and received ${received} F44 back`);
+    assert.ok(received <= paid);
+  });
+  it("Buy agent token by spending an exact F44 amount", async() => {
+    const f44In = new anchor.BN(5 * 10 ** 6);
+    const associatedUser = await getAssociatedTokenAddress(
+      agentMint,
+      buyer.publicKey
+    );
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      buyer.publicKey
+    );
+    const f44Before = (await getAccount(connection, associatedUserF44Account)).amount;
+    const tokenBefore = (await getAccount(connection, associatedUser)).amount;
+
+    await program.rpc.buyExactIn(f44In, new anchor.BN(1), {
+      accounts: {
+        global,
+        mint: agentMint,
+        bondingCurve,
+        associatedBondingCurve,
+        associatedUser,
+        f44Mint,
+        f44Vault,
+        quoteConfig: null,
+        associatedUserF44Account,
+        feeRecipient: feeRecipientAccount,
+        referrer: null,
+        referral: null,
+        priceOracle: null,
+        pool,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    const f44After = (await getAccount(connection, associatedUserF44Account)).amount;
+    const tokenAfter = (await getAccount(connection, associatedUser)).amount;
+
+    const spent = f44Before - f44After;
+    console.log(`spent ${spent} F44 on ${tokenAfter - tokenBefore} agent tokens`);
+    assert.ok(spent <= BigInt(f44In.toString()));
+    assert.ok(tokenAfter > tokenBefore);
+  });
+  it("Sell agent token for an exact F44 amount", async() => {
+    const f44Out = new anchor.BN(2 * 10 ** 6);
+    const associatedUser = await getAssociatedTokenAddress(
+      agentMint,
+      buyer.publicKey
+    );
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      buyer.publicKey
+    );
+    const f44Before = (await getAccount(connection, associatedUserF44Account)).amount;
+    const tokenBefore = (await getAccount(connection, associatedUser)).amount;
+
+    await program.rpc.sellExactOut(f44Out, new anchor.BN(tokenBefore.toString()), {
+      accounts: {
+        global,
+        mint: agentMint,
+        bondingCurve,
+        associatedBondingCurve,
+        associatedUser,
+        f44Mint,
+        f44Vault,
+        quoteConfig: null,
+        associatedUserF44Account,
+        feeRecipient: feeRecipientAccount,
+        referrer: null,
+        referral: null,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    const f44After = (await getAccount(connection, associatedUserF44Account)).amount;
+    const tokenAfter = (await getAccount(connection, associatedUser)).amount;
+
+    console.log(`burnt ${tokenBefore - tokenAfter} agent tokens for ${f44After - f44Before} F44`);
+    assert.ok(f44After - f44Before >= BigInt(f44Out.toString()));
+    assert.ok(tokenAfter < tokenBefore);
+  });
+  it("Quotes match the curve state and the fees charged", async() => {
+    const amount = new anchor.BN(10_000_000);
+    const accounts = {
+      global,
+      mint: agentMint,
+      bondingCurve,
+      associatedBondingCurve,
+      f44Mint,
+      priceOracle: null,
+    };
+    const state = await program.methods.quoteCurveState().accounts(accounts).view();
+    const curveData = await program.account.bondingCurve.fetch(bondingCurve);
+    assert.equal(state.tokenReserves.toString(), curveData.tokenReserves.toString());
+    assert.equal(state.currentPrice.toString(), curveData.currentPrice.toString());
+
+    const buyQuote = await program.methods.quoteBuy(amount).accounts(accounts).view();
+    assert.equal(buyQuote.tokenAmount.toString(), amount.toString());
+    assert.equal(
+      buyQuote.totalF44.toString(),
+      buyQuote.f44Cost.add(buyQuote.fee).add(buyQuote.creatorFee).toString()
+    );
+    assert.ok(buyQuote.priceAfter.gte(state.currentPrice));
+
+    const sellQuote = await program.methods.quoteSell(amount).accounts(accounts).view();
+    assert.ok(sellQuote.f44Proceeds.lte(buyQuote.f44Cost));
+    assert.equal(
+      sellQuote.f44Received.toString(),
+      sellQuote.f44Proceeds.sub(sellQuote.fee).sub(sellQuote.creatorFee).toString()
+    );
+    assert.ok(sellQuote.priceAfter.lte(state.currentPrice));
+  });
+  it("The creator claims the fees accrued by trades on their curve", async() => {
+    const before = await program.account.bondingCurve.fetch(bondingCurve);
+    assert.ok(before.creator.equals(user.publicKey));
+    assert.ok(before.creatorFees.gtn(0));
+
+    const associatedCreatorF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      user.publicKey
+    );
+    const balanceBefore = (await getAccount(connection, associatedCreatorF44Account)).amount;
+    await program.rpc.claimCreatorFees({
+      accounts: {
+        global,
+        mint: agentMint,
+        bondingCurve,
+        f44Mint,
+        f44Vault,
+        quoteConfig: null,
+        associatedCreatorF44Account,
+        creator: user.publicKey,
+        systemProgram: SystemProgram.programId,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [user]
+    });
+    const balanceAfter = (await getAccount(connection, associatedCreatorF44Account)).amount;
+    const after = await program.account.bondingCurve.fetch(bondingCurve);
+
+    assert.equal((balanceAfter - balanceBefore).toString(), before.creatorFees.toString());
+    assert.equal(after.creatorFees.toNumber(), 0);
+  });
+  it("A referrer earns a share of the trading fee and claims it", async() => {
+    const [referral] = PublicKey.findProgramAddressSync(
+      [Buffer.from("REFERRAL-SEED"), user.publicKey.toBuffer()],
+      program.programId
+    );
+    const associatedUser = await getAssociatedTokenAddress(
+      agentMint,
+      buyer.publicKey
+    );
+    const associatedUserF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      buyer.publicKey
+    );
+    const tokenAmount = new anchor.BN(50_000_000);
+    const balance = (await getAccount(connection, associatedUserF44Account)).amount;
+    await program.rpc.buy(tokenAmount, new anchor.BN(balance.toString()), {
+      accounts: {
+        global,
+        mint: agentMint,
+        bondingCurve,
+        associatedBondingCurve,
+        associatedUser,
+        f44Mint,
+        f44Vault,
+        quoteConfig: null,
+        associatedUserF44Account,
+        feeRecipient: feeRecipientAccount,
+        referrer: user.publicKey,
+        referral,
+        priceOracle: null,
+        pool,
+        user: buyer.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [buyer]
+    });
+    const referralData = await program.account.referral.fetch(referral);
+    assert.ok(referralData.referrer.equals(user.publicKey));
+    assert.ok(referralData.unclaimed.gtn(0));
+    assert.equal(referralData.totalEarned.toString(), referralData.unclaimed.toString());
+
+    const associatedReferrerF44Account = await getAssociatedTokenAddress(
+      f44Mint,
+      user.publicKey
+    );
+    const balanceBefore = (await getAccount(connection, associatedReferrerF44Account)).amount;
+    await program.rpc.claimReferral({
+      accounts: {
+        global,
+        referral,
+        f44Mint,
+        f44Vault,
+        associatedReferrerF44Account,
+        referrer: user.publicKey,
+        systemProgram: SystemProgram.programId,
+        f44TokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        clock: SYSVAR_CLOCK_PUBKEY
+      },
+      signers: [user]
+    });
+    const balanceAfter = (await getAccount(connection, associatedReferrerF44Account)).amount;
+    const after = await program.account.referral.fetch(referral);
+
+    assert.equal((balanceAfter - balanceBefore).toString(), referralData.unclaimed.toString());
+    assert.equal(after.unclaimed.toNumber(), 0);
+  });
+  it("The buy that completes a curve is only filled up to the market cap limit", async() => {
+    const launched = await launchCurve(new anchor.BN(Date.now() + 2));
+    const requested = (await program.account.bondingCurve.fetch(launched.curve)).tokenTotalSupply;
+    const quote = await program.methods.quoteBuy(requested).accounts({
+      global,
+      mint: launched.mint,
+      bondingCurve: launched.curve,
+      associatedBondingCurve: launched.vault,
+      f44Mint,
+      priceOracle: null,
+    }).view();
+
+    // asking for the whole supply pays only for the part up to the limit
+    const associatedUser = await completeCurve(launched);
+
+    const curveData = await program.account.bondingCurve.fetch(launched.curve);
+    const filled = (await getAccount(connection, associatedUser, undefined, TOKEN_2022_PROGRAM_ID)).amount;
+    assert.ok(curveData.complete);
+    assert.equal(filled.toString(), curveData.tokenReserves.toString());
+    assert.ok(curveData.tokenReserves.lt(requested));
+    // the quote clips the same way
+    assert.equal(quote.tokenAmount.toString(), filled.toString());
+    assert.ok(quote.completes);
+  });
+
+  async function calculateBuyF44Cost(f44Amount: number) {
+    try {
+      const bondingCurveData = await program.account.bondingCurve.fetch(bondingCurve);
+      console.log("bondingCurveData->", bondingCurveData);
+      // convert the fixed-point curve to whole-token units for an estimate
+      const { initialPrice: rawPrice, curveSlope: rawSlope } = bondingCurveData.curve.linear;
+      const initialPrice = Number(rawPrice.toString()) / PRICE_SCALE;
+      const curveSlope = Number(rawSlope.toString()) / PRICE_SCALE * 10 ** 6;
+      const tokenReserves = Number(bondingCurveData.tokenReserves.toString()) / 10 ** 6;
+      const A = curveSlope / 2;
+      const B = curveSlope * tokenReserves + initialPrice;
+      const C = -f44Amount;
+
+      const x = (-B + Math.sqrt(Math.pow(B,2) - 4 * A * C)) / (2 * A);
+      return x;
+    } catch (error) {
+      console.log(error);
+    }
+  }
+
+  async function calculateF44SellCost(tokenAmount: number) {
+    try {
+      const bondingCurveData = await program.account.bondingCurve.fetch(bondingCurve);
+      console.log("bondingCurveData->", bondingCurveData);
+      const { initialPrice: rawPrice, curveSlope: rawSlope } = bondingCurveData.curve.linear;
+      const initialPrice = Number(rawPrice.toString()) / PRICE_SCALE;
+      const curveSlope = Number(rawSlope.toString()) / PRICE_SCALE * 10 ** 6;
+      const tokenReserves = Number(bondingCurveData.tokenReserves.toString()) / 10 ** 6;
+    
+      let firstPrice = initialPrice + curveSlope * tokenReserves;
+      let lastPrice = initialPrice + curveSlope * (tokenReserves - tokenAmount);
+      console.log("average price is ", (firstPrice + lastPrice ) / 2 );
+      let x = (firstPrice + lastPrice) / 2 * tokenAmount;
+      console.log("Sell F44 amount is ", x );
+
+      return x;
+    } catch (error) {
+      console.log(error);
+    }
+  }
+});